@@ -0,0 +1,202 @@
+use std::ops::{Add, Mul};
+
+use crate::{matrix::Matrix4x4, object::Object, pos::Pos4D, projection::{Projection, ProjectionType}, rotor::Rotor, transform::Transform};
+
+/// How to move from a keyframe to the next one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// Hold the value of the keyframe until the next one is reached
+    Step,
+    Linear,
+    /// Catmull-Rom spline through the neighbouring keyframes, rotations are slerped
+    Cubic,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Interpolation {
+    /// Remap the linear progress `t` in 0..1 through the easing curve
+    fn ease(&self, t: f32) -> f32 {
+        use Interpolation::*;
+
+        match self {
+            Step => 0.0,
+            Linear | Cubic => t,
+            EaseIn => t * t,
+            EaseOut => t * (2.0 - t),
+            EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// What happens when the time runs past the last keyframe
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Playback {
+    /// Stop at the last keyframe
+    Once,
+    /// Jump back to the first keyframe
+    Loop,
+    /// Play the timeline backwards until the first keyframe is reached again
+    PingPong,
+}
+
+/// State of the scene at a certain point in time
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    /// Time in seconds
+    pub time: f32,
+    pub rotation: Rotor,
+    pub translation: Pos4D,
+    pub scale: f32,
+    /// Rotation of the camera around the origin, applied after the object transform
+    pub camera: Rotor,
+    pub projection_scale: f32,
+    /// Interpolation used on the way to the next keyframe
+    pub interpolation: Interpolation,
+}
+
+impl Keyframe {
+    /// Create a keyframe at `time` without any transformation
+    pub fn new(time: f32) -> Self {
+        Self {
+            time,
+            rotation: Rotor::identity(),
+            translation: Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 0.0 },
+            scale: 1.0,
+            camera: Rotor::identity(),
+            projection_scale: 1.0,
+            interpolation: Interpolation::Linear,
+        }
+    }
+
+    /// Apply the keyframe transformation to an object
+    pub fn apply(&self, object: &Object<Pos4D>) -> Object<Pos4D> {
+        object
+            .scale(self.scale)
+            .rotate(Matrix4x4::from(self.rotation))
+            .translate(self.translation)
+            .rotate(Matrix4x4::from(self.camera))
+    }
+
+    /// Projection with the scale of this keyframe
+    pub fn projection(&self, r#type: ProjectionType) -> Projection {
+        Projection::new(r#type, self.projection_scale)
+    }
+}
+
+/// Timeline of keyframes that can be sampled at any point in time
+#[derive(Clone, Debug)]
+pub struct Animation {
+    keyframes: Vec<Keyframe>,
+    pub playback: Playback,
+}
+
+impl Animation {
+    /// Create an animation, the keyframes are sorted by time
+    pub fn new(mut keyframes: Vec<Keyframe>, playback: Playback) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Self { keyframes, playback }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Length of a single pass through the timeline in seconds
+    pub fn duration(&self) -> f32 {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    /// Interpolated state at `time` seconds, independent of how often it is sampled
+    pub fn sample(&self, time: f32) -> Keyframe {
+        let (first, last) = match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Keyframe::new(time),
+        };
+
+        let duration = self.duration();
+        let time = if duration <= 0.0 {
+            first.time
+        } else {
+            let local = time - first.time;
+
+            first.time + match self.playback {
+                Playback::Once => local.clamp(0.0, duration),
+                Playback::Loop => local.rem_euclid(duration),
+                Playback::PingPong => {
+                    let local = local.rem_euclid(2.0 * duration);
+                    if local > duration { 2.0 * duration - local } else { local }
+                }
+            }
+        };
+
+        // Find the segment containing the time
+        let next = self.keyframes.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return Keyframe { time, ..*first };
+        }
+        if next >= self.keyframes.len() {
+            return Keyframe { time, ..*last };
+        }
+
+        let index = next - 1;
+        let a = &self.keyframes[index];
+        let b = &self.keyframes[next];
+
+        let t = a.interpolation.ease((time - a.time) / (b.time - a.time));
+
+        let (translation, scale, projection_scale) = if a.interpolation == Interpolation::Cubic {
+            // Neighbouring keyframes, repeating the ends of the timeline
+            let before = &self.keyframes[index.saturating_sub(1)];
+            let after = &self.keyframes[(next + 1).min(self.keyframes.len() - 1)];
+
+            (
+                catmull_rom(before.translation, a.translation, b.translation, after.translation, t),
+                catmull_rom(before.scale, a.scale, b.scale, after.scale, t),
+                catmull_rom(before.projection_scale, a.projection_scale, b.projection_scale, after.projection_scale, t),
+            )
+        } else {
+            (
+                lerp(a.translation, b.translation, t),
+                lerp(a.scale, b.scale, t),
+                lerp(a.projection_scale, b.projection_scale, t),
+            )
+        };
+
+        Keyframe {
+            time,
+            rotation: a.rotation.slerp(b.rotation, t),
+            translation,
+            scale,
+            camera: a.camera.slerp(b.camera, t),
+            projection_scale,
+            interpolation: a.interpolation,
+        }
+    }
+}
+
+fn lerp<T>(a: T, b: T, t: f32) -> T
+where
+    T: Add<Output = T> + Mul<f32, Output = T>,
+{
+    a * (1.0 - t) + b * t
+}
+
+/// Uniform Catmull-Rom spline between p1 and p2
+fn catmull_rom<T>(p0: T, p1: T, p2: T, p3: T, t: f32) -> T
+where
+    T: Add<Output = T> + Mul<f32, Output = T>,
+{
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    p0 * (0.5 * (-t3 + 2.0 * t2 - t))
+        + p1 * (0.5 * (3.0 * t3 - 5.0 * t2 + 2.0))
+        + p2 * (0.5 * (-3.0 * t3 + 4.0 * t2 + t))
+        + p3 * (0.5 * (t3 - t2))
+}
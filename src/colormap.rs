@@ -0,0 +1,100 @@
+use crate::render::Color;
+
+/// Color gradient defined by color stops at positions between 0 and 1, interpolated in Oklab
+#[derive(Clone, Debug)]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+}
+
+impl Gradient {
+    /// Create a gradient from `(position, color)` stops, the stops are sorted by position
+    pub fn new(mut stops: Vec<(f32, Color)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self { stops }
+    }
+
+    /// Create a gradient with the colors spread evenly between 0 and 1
+    pub fn evenly_spaced(colors: &[Color]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+
+        Self::new(colors.iter().enumerate().map(|(i, &color)| (i as f32 / last, color)).collect())
+    }
+
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Color at position `t`, positions outside of the stops get the color of the nearest stop
+    pub fn sample(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::Black,
+        };
+
+        if t <= first.0 { return first.1; }
+        if t >= last.0 { return last.1; }
+
+        // Find the two stops around t
+        let next = self.stops.partition_point(|stop| stop.0 <= t);
+        let (a_t, a) = self.stops[next - 1];
+        let (b_t, b) = self.stops[next];
+
+        let factor = if b_t > a_t { (t - a_t) / (b_t - a_t) } else { 0.0 };
+
+        a.mix(b, factor)
+    }
+}
+
+/// Built in scientific colormaps
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Colormap {
+    /// Perceptually uniform, dark blue to yellow
+    Viridis,
+    /// Perceptually uniform, black to light yellow through purple
+    Magma,
+    /// Rainbow with smooth lightness, dark blue to dark red
+    Turbo,
+    /// Diverging, blue to red through light grey
+    Coolwarm,
+}
+
+// Samples of the matplotlib colormaps at evenly spaced positions
+const VIRIDIS: [u32; 9] = [0x440154, 0x472d7b, 0x3b528b, 0x2c728e, 0x21918c, 0x28ae80, 0x5ec962, 0xaddc30, 0xfde725];
+const MAGMA: [u32; 9] = [0x000004, 0x1c1044, 0x4f127b, 0x812581, 0xb5367a, 0xe55064, 0xfb8761, 0xfec287, 0xfcfdbf];
+const COOLWARM: [u32; 9] = [0x3b4cc0, 0x6282ea, 0x8db0fe, 0xb8d0f9, 0xdddddd, 0xf5c4ad, 0xf49a7b, 0xde604d, 0xb40426];
+
+impl Colormap {
+    /// Color at position `t` between 0 and 1
+    pub fn sample(&self, t: f32) -> Color {
+        match self {
+            // Turbo has a polynomial approximation, no need for stops
+            Colormap::Turbo => turbo(t.clamp(0.0, 1.0)),
+            _ => Gradient::from(*self).sample(t),
+        }
+    }
+}
+
+impl From<Colormap> for Gradient {
+    fn from(val: Colormap) -> Self {
+        let hex = |c: &u32| Color::RGB((c >> 16) as u8, (c >> 8) as u8, *c as u8);
+
+        match val {
+            Colormap::Viridis => Gradient::evenly_spaced(&VIRIDIS.iter().map(hex).collect::<Vec<_>>()),
+            Colormap::Magma => Gradient::evenly_spaced(&MAGMA.iter().map(hex).collect::<Vec<_>>()),
+            Colormap::Coolwarm => Gradient::evenly_spaced(&COOLWARM.iter().map(hex).collect::<Vec<_>>()),
+            Colormap::Turbo => Gradient::evenly_spaced(&(0..=16).map(|i| turbo(i as f32 / 16.0)).collect::<Vec<_>>()),
+        }
+    }
+}
+
+/// Polynomial approximation of the turbo colormap by Anton Mikhailov
+fn turbo(t: f32) -> Color {
+    let r = 0.13572138 + t * (4.6153927 + t * (-42.660324 + t * (132.13109 + t * (-152.9424 + t * 59.28638))));
+    let g = 0.09140261 + t * (2.1941884 + t * (4.8429666 + t * (-14.185034 + t * (4.2772985 + t * 2.829566))));
+    let b = 0.1066733 + t * (12.641946 + t * (-60.582047 + t * (110.36277 + t * (-89.90311 + t * 27.34825))));
+
+    let to_u8 = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    Color::RGB(to_u8(r), to_u8(g), to_u8(b))
+}
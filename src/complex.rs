@@ -0,0 +1,172 @@
+use std::{f32::consts::PI, ops};
+
+use crate::{
+    object::Object,
+    parametric::parametric_surface_4d,
+    pos::Pos4D,
+    render::Color,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Complex {
+    pub re: f32,
+    pub im: f32,
+}
+
+impl Complex {
+    pub fn new(re: f32, im: f32) -> Self {
+        Self { re, im }
+    }
+
+    pub fn from_polar(r: f32, angle: f32) -> Self {
+        Self { re: r * angle.cos(), im: r * angle.sin() }
+    }
+
+    pub fn abs(&self) -> f32 {
+        self.re.hypot(self.im)
+    }
+
+    /// Angle with the positive real axis, between -pi and pi
+    pub fn arg(&self) -> f32 {
+        self.im.atan2(self.re)
+    }
+
+    pub fn conj(&self) -> Self {
+        Self { re: self.re, im: -self.im }
+    }
+
+    pub fn exp(&self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+
+    /// Principal logarithm, with the imaginary part between -pi and pi
+    pub fn ln(&self) -> Self {
+        Self { re: self.abs().ln(), im: self.arg() }
+    }
+
+    /// Principal square root, with a cut along the negative real axis
+    pub fn sqrt(&self) -> Self {
+        Self::from_polar(self.abs().sqrt(), self.arg() / 2.0)
+    }
+
+    pub fn powi(&self, n: i32) -> Self {
+        Self::from_polar(self.abs().powi(n), self.arg() * n as f32)
+    }
+}
+
+impl ops::Add for Complex {
+    type Output = Complex;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Complex { re: self.re + rhs.re, im: self.im + rhs.im }
+    }
+}
+
+impl ops::Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Complex { re: self.re - rhs.re, im: self.im - rhs.im }
+    }
+}
+
+impl ops::Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Self::Output {
+        Complex { re: -self.re, im: -self.im }
+    }
+}
+
+impl ops::Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+impl ops::Mul<f32> for Complex {
+    type Output = Complex;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Complex { re: self.re * rhs, im: self.im * rhs }
+    }
+}
+
+impl ops::Div for Complex {
+    type Output = Complex;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let len = rhs.re * rhs.re + rhs.im * rhs.im;
+
+        (self * rhs.conj()) * (1.0 / len)
+    }
+}
+
+/// Part of the complex plane a graph is sampled over
+#[derive(Clone, Copy, Debug)]
+pub enum Domain {
+    /// Rectangle between two opposite corners
+    Rect { min: Complex, max: Complex },
+    /// Disk or annulus around the origin
+    Polar { r_min: f32, r_max: f32 },
+}
+
+/// Graph of `f` as a surface in 4D, with z and w holding the real and imaginary part of `f(x + iy)`
+///
+/// The nodes are colored by the argument of `f`, going around the hues once for every turn around the origin.
+pub fn complex_graph<F>(domain: Domain, res: usize, f: F) -> Object<Pos4D>
+where
+    F: Fn(Complex) -> Complex,
+{
+    match domain {
+        Domain::Rect { min, max } => graph(res, res, (false, false), |u, v| {
+            let z = Complex::new(min.re + (max.re - min.re) * u, min.im + (max.im - min.im) * v);
+
+            (z, f(z))
+        }),
+        Domain::Polar { r_min, r_max } => polar_graph(r_min, r_max, res, 1, |z, _| f(z)),
+    }
+}
+
+/// Graph of a function going around the origin `turns` times, so a multivalued function can follow
+/// every one of its branches instead of jumping at a cut
+///
+/// `f` gets the angle of z without wrapping it, from 0 to `turns` times 2 pi.
+pub fn polar_graph<F>(r_min: f32, r_max: f32, res: usize, turns: usize, f: F) -> Object<Pos4D>
+where
+    F: Fn(Complex, f32) -> Complex,
+{
+    let turns = turns.max(1);
+
+    // The last turn ends where the first started, so the angle wraps around
+    graph(res, res * turns, (false, true), |u, v| {
+        let (r, angle) = (r_min + (r_max - r_min) * u, 2.0 * PI * turns as f32 * v);
+        let z = Complex::from_polar(r, angle);
+
+        (z, f(z, angle))
+    })
+}
+
+fn graph<F>(u_res: usize, v_res: usize, wrap: (bool, bool), f: F) -> Object<Pos4D>
+where
+    F: Fn(f32, f32) -> (Complex, Complex),
+{
+    let mut object = parametric_surface_4d(u_res, v_res, wrap, Color::White, |u, v| {
+        let (z, value) = f(u, v);
+
+        Pos4D { x: z.re, y: z.im, z: value.re, w: value.im }
+    });
+
+    for node in object.nodes.iter_mut() {
+        let hue = Complex::new(node.pos.z, node.pos.w).arg().to_degrees().rem_euclid(360.0);
+
+        node.color = Color::HSV(hue as u16, 255, 255);
+    }
+
+    object
+}
@@ -0,0 +1,95 @@
+use crate::{colormap::Gradient, object::Object, pos::{Len, Pos4D}, render::Color};
+
+/// Fade geometry as it recedes along the view axis
+#[derive(Clone, Copy, Debug)]
+pub struct Fog {
+    /// Distance along the view axis where the fog starts
+    pub start: f32,
+    /// Distance along the view axis where the geometry is completely faded into the fog color
+    pub end: f32,
+    pub color: Color,
+}
+
+impl Fog {
+    /// Fog fading to transparent black
+    pub fn new(start: f32, end: f32) -> Self {
+        Self { start, end, color: Color::RGBA(0, 0, 0, 0) }
+    }
+
+    /// Amount of fog between 0 and 1 at a distance along the view axis
+    fn density(&self, distance: f32) -> f32 {
+        if self.end == self.start {
+            return if distance < self.start { 0.0 } else { 1.0 };
+        }
+
+        let t = ((distance - self.start) / (self.end - self.start)).clamp(0.0, 1.0);
+
+        // Smoothstep so the fog has no hard edges
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+/// Recolor nodes based on their position along a 4D view axis, making the fourth dimension readable
+#[derive(Clone, Debug)]
+pub struct DepthCue {
+    /// Direction along which the depth is measured, w by default
+    pub axis: Pos4D,
+    /// Distances mapped to the start and end of the gradient, the range of the object if None
+    pub range: Option<(f32, f32)>,
+    /// Colors replacing the node colors, the node colors are kept if None
+    pub gradient: Option<Gradient>,
+    pub fog: Option<Fog>,
+}
+
+impl DepthCue {
+    /// Color nodes by their w coordinate using the gradient
+    pub fn new(gradient: Gradient) -> Self {
+        Self {
+            axis: Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 1.0 },
+            range: None,
+            gradient: Some(gradient),
+            fog: None,
+        }
+    }
+
+    /// Distance of a position along the view axis
+    pub fn distance(&self, pos: Pos4D) -> f32 {
+        let len = self.axis.len();
+
+        if len == 0.0 { 0.0 } else { (pos >> self.axis) / len }
+    }
+
+    /// Copy of the object with the node colors replaced by the depth cue colors
+    pub fn apply(&self, object: &Object<Pos4D>) -> Object<Pos4D> {
+        let distances: Vec<f32> = object.nodes.iter().map(|node| self.distance(node.pos)).collect();
+
+        let (min, max) = self.range.unwrap_or_else(|| {
+            distances.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &d| (min.min(d), max.max(d)))
+        });
+
+        let mut object = object.clone();
+
+        for (node, &distance) in object.nodes.iter_mut().zip(distances.iter()) {
+            if let Some(gradient) = &self.gradient {
+                let t = if max > min { (distance - min) / (max - min) } else { 0.5 };
+
+                node.color = gradient.sample(t);
+            }
+
+            if let Some(fog) = &self.fog {
+                let density = fog.density(distance);
+                let color = node.color.get_rgba();
+                let fog_color = fog.color.get_rgba();
+
+                let mut rgba: [u8; 4] = [0; 4];
+                for i in 0..4 {
+                    rgba[i] = (color[i] as f32 + (fog_color[i] as f32 - color[i] as f32) * density).round() as u8;
+                }
+
+                node.color = Color::RGBA(rgba[0], rgba[1], rgba[2], rgba[3]);
+            }
+        }
+
+        object
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fs::{self, File}, io::BufWriter, path::Path, sync::{Arc, Mutex}};
+
+use crate::{animation::Animation, object::Object, pos::Pos4D, projection::ProjectionType, render::{DepthCompare, Filter, Screen}};
+
+/// File formats an animation can be written to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Gif,
+    Apng,
+    /// A directory of numbered png files, `frame_0000.png`, `frame_0001.png`, ...
+    PngSequence,
+}
+
+impl ExportFormat {
+    /// Guess the format from a path, `.gif` and `.apng` files or a directory for anything else
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("gif") => ExportFormat::Gif,
+            Some("apng") => ExportFormat::Apng,
+            _ => ExportFormat::PngSequence,
+        }
+    }
+}
+
+/// Render `frame_count` frames of an animation without a window, `timestep` seconds apart
+///
+/// The frames only depend on the arguments, so rendering the same animation twice gives identical images.
+///
+/// Every pixel is built from `samples` x `samples` samples, 1 disables supersampling.
+pub fn render_animation(
+    object: &Object<Pos4D>,
+    animation: &Animation,
+    r#type: ProjectionType,
+    size: (usize, usize),
+    samples: usize,
+    frame_count: usize,
+    timestep: f32,
+) -> Vec<Screen> {
+    (0..frame_count).map(|i| {
+        let screen = Arc::new(Mutex::new(Screen::with_supersampling(size.0, size.1, samples, Filter::Tent)));
+
+        let frame = animation.sample(i as f32 * timestep);
+        frame.apply(object).draw(Arc::clone(&screen), frame.projection(r#type));
+
+        let mut screen = Arc::try_unwrap(screen)
+            .ok()
+            .and_then(|screen| screen.into_inner().ok())
+            .expect("Screen is still in use after drawing");
+        screen.resolve();
+
+        screen
+    }).collect()
+}
+
+/// Write the frames in the given format, `timestep` is the time between frames in seconds
+pub fn export(frames: &[Screen], format: ExportFormat, path: &Path, timestep: f32) -> Result<(), &'static str> {
+    match format {
+        ExportFormat::Gif => write_gif(frames, path, timestep),
+        ExportFormat::Apng => write_apng(frames, path, timestep),
+        ExportFormat::PngSequence => write_png_sequence(frames, path),
+    }
+}
+
+/// Write a single frame to a png file
+pub fn write_png(screen: &Screen, path: &Path) -> Result<(), &'static str> {
+    let file = File::create(path).map_err(|_| "Failed to create png file")?;
+    let (width, height) = screen.size();
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(|_| "Failed to write png header")?;
+    writer.write_image_data(screen.get_slice()).map_err(|_| "Failed to write png image data")?;
+
+    Ok(())
+}
+
+/// Write the depth buffer of a frame to a grayscale png file, normalized so the nearest pixel is white
+pub fn write_depth_png(screen: &Screen, path: &Path) -> Result<(), &'static str> {
+    let file = File::create(path).map_err(|_| "Failed to create png file")?;
+    let (width, height) = screen.size();
+
+    // Keep the gray value and alpha of every pixel
+    let gray_alpha: Vec<u8> = screen.depth_to_rgba().chunks(4).flat_map(|p| [p[0], p[3]]).collect();
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::GrayscaleAlpha);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(|_| "Failed to write png header")?;
+    writer.write_image_data(&gray_alpha).map_err(|_| "Failed to write png image data")?;
+
+    Ok(())
+}
+
+/// Write the raw depth buffer of a frame to a grayscale pfm file, pixels without depth get the far depth
+pub fn write_depth_pfm(screen: &Screen, path: &Path) -> Result<(), &'static str> {
+    let (width, height) = screen.size();
+    let far = if screen.depth_compare == DepthCompare::Greater { 0.0 } else { 1.0 };
+
+    // Negative scale marks the data as little endian
+    let mut data = format!("Pf\n{} {}\n-1.0\n", width, height).into_bytes();
+
+    // Pfm stores the rows from bottom to top
+    for y in (0..height).rev() {
+        for x in 0..width {
+            data.extend_from_slice(&screen.pixel_depth(x, y).unwrap_or(far).to_le_bytes());
+        }
+    }
+
+    fs::write(path, data).map_err(|_| "Failed to write pfm file")
+}
+
+/// Write every frame to its own numbered png file inside `directory`
+pub fn write_png_sequence(frames: &[Screen], directory: &Path) -> Result<(), &'static str> {
+    fs::create_dir_all(directory).map_err(|_| "Failed to create output directory")?;
+
+    for (i, frame) in frames.iter().enumerate() {
+        write_png(frame, &directory.join(format!("frame_{:04}.png", i)))?;
+    }
+
+    Ok(())
+}
+
+/// Write the frames to a looping animated png
+pub fn write_apng(frames: &[Screen], path: &Path, timestep: f32) -> Result<(), &'static str> {
+    let (width, height) = frames.first().ok_or("No frames to export")?.size();
+    let file = File::create(path).map_err(|_| "Failed to create apng file")?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len() as u32, 0).map_err(|_| "Failed to mark png as animated")?;
+    encoder.set_frame_delay((timestep * 1000.0).round() as u16, 1000).map_err(|_| "Failed to set apng frame delay")?;
+
+    let mut writer = encoder.write_header().map_err(|_| "Failed to write apng header")?;
+    for frame in frames {
+        if frame.size() != (width, height) {
+            return Err("All frames of an animation need to be the same size");
+        }
+
+        writer.write_image_data(frame.get_slice()).map_err(|_| "Failed to write apng frame")?;
+    }
+
+    writer.finish().map_err(|_| "Failed to finish apng file")
+}
+
+/// Write the frames to a looping gif, fully transparent pixels stay transparent
+pub fn write_gif(frames: &[Screen], path: &Path, timestep: f32) -> Result<(), &'static str> {
+    let (width, height) = frames.first().ok_or("No frames to export")?.size();
+    let file = File::create(path).map_err(|_| "Failed to create gif file")?;
+
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+        .map_err(|_| "Failed to write gif header")?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(|_| "Failed to make gif loop")?;
+
+    // Gif delays are in units of 10ms
+    let delay = (timestep * 100.0).round() as u16;
+
+    for frame in frames {
+        if frame.size() != (width, height) {
+            return Err("All frames of an animation need to be the same size");
+        }
+
+        let mut rgba = frame.get_slice().to_vec();
+        let mut gif_frame = gif::Frame::from_rgba_speed(width as u16, height as u16, &mut rgba, 10);
+        gif_frame.delay = delay;
+        gif_frame.dispose = gif::DisposalMethod::Background;
+
+        encoder.write_frame(&gif_frame).map_err(|_| "Failed to write gif frame")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::{
+    colormap::Colormap,
+    complex::Complex,
+    implicit::marching_pentachora,
+    object::{Node, Object},
+    pos::{Len, Pos4D},
+    quaternion::Quaternion,
+    render::Color,
+};
+
+/// Points escaping past this distance never come back
+const ESCAPE_RADIUS: f32 = 4.0;
+
+/// Fractal defined by how quickly iterating from a point runs off to infinity
+#[derive(Clone, Copy, Debug)]
+pub enum Fractal {
+    /// Quaternion Julia set, iterating q² + c starting at the point
+    Julia(Quaternion),
+    /// Complex Mandelbrot and Julia sets in one, iterating z² + c with c = x + iy starting at z = z + iw
+    ///
+    /// The plane z = w = 0 is the Mandelbrot set, every plane with fixed x and y a Julia set.
+    Mandelbrot,
+}
+
+impl Fractal {
+    /// Number of iterations after which the point escapes, `None` if it is still bounded after `max_iterations`
+    pub fn escape_time(&self, pos: Pos4D, max_iterations: usize) -> Option<usize> {
+        match self {
+            Fractal::Julia(c) => {
+                let mut q = Quaternion::from(pos);
+
+                (0..max_iterations).find(|_| {
+                    q = q * q + *c;
+                    q.len() > ESCAPE_RADIUS
+                })
+            }
+            Fractal::Mandelbrot => {
+                let c = Complex::new(pos.x, pos.y);
+                let mut z = Complex::new(pos.z, pos.w);
+
+                (0..max_iterations).find(|_| {
+                    z = z * z + c;
+                    z.abs() > ESCAPE_RADIUS
+                })
+            }
+        }
+    }
+}
+
+/// Bounded points of a grid of `res` steps between `min` and `max` next to an escaping point, as a point cloud
+///
+/// Every point is colored by how long its slowest escaping neighbour takes to escape, from dark next to points
+/// escaping right away to light next to the ones that almost stayed.
+pub fn fractal_points(fractal: Fractal, min: Pos4D, max: Pos4D, res: usize, max_iterations: usize) -> Object<Pos4D> {
+    let res = res.max(1);
+    let count = res + 1;
+    let strides = [1, count, count * count, count * count * count];
+
+    let point = |index: usize| {
+        let [i, j, k, l] = strides.map(|stride| (index / stride % count) as f32 / res as f32);
+
+        Pos4D {
+            x: min.x + (max.x - min.x) * i,
+            y: min.y + (max.y - min.y) * j,
+            z: min.z + (max.z - min.z) * k,
+            w: min.w + (max.w - min.w) * l,
+        }
+    };
+
+    let times: Vec<Option<usize>> = (0..count.pow(4)).map(|index| fractal.escape_time(point(index), max_iterations)).collect();
+
+    let mut nodes = Vec::new();
+    for (index, time) in times.iter().enumerate() {
+        if time.is_some() {
+            continue;
+        }
+
+        // Neighbours one step away along every axis, the ones past the border of the grid count as escaped
+        let neighbours = strides.iter().flat_map(|&stride| {
+            let coord = index / stride % count;
+
+            [
+                if coord > 0 { times[index - stride] } else { Some(0) },
+                if coord < res { times[index + stride] } else { Some(0) },
+            ]
+        });
+
+        if let Some(slowest) = neighbours.flatten().max() {
+            let color = Colormap::Magma.sample((slowest + 1) as f32 / max_iterations as f32);
+
+            nodes.push(Node { pos: point(index), color, r: 1 });
+        }
+    }
+
+    Object::new(nodes, Vec::new())
+}
+
+/// Hypersurface between the bounded and escaping points of a grid of `res` steps between `min` and `max`
+///
+/// The surface is placed halfway between neighbouring bounded and escaping samples, so detail finer than the grid is lost.
+pub fn fractal_mesh(fractal: Fractal, min: Pos4D, max: Pos4D, res: usize, max_iterations: usize, color: Color) -> Object<Pos4D> {
+    marching_pentachora(|pos| match fractal.escape_time(pos, max_iterations) {
+        Some(_) => 1.0,
+        None => -1.0,
+    }, min, max, res, color)
+}
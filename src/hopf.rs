@@ -0,0 +1,118 @@
+use std::f32::consts::PI;
+
+use crate::{
+    complex::Complex,
+    object::{CullMode, Edge, Node, Object},
+    parametric::parametric_surface_4d,
+    pos::{Len, Pos3D, Pos4D},
+    render::Color,
+};
+
+/// How the fibers of the Hopf fibration are drawn
+#[derive(Clone, Copy, Debug)]
+pub enum FiberStyle {
+    /// Closed loops of edges
+    Loop,
+    /// Tubes on the 3-sphere around every fiber, which stay round after the stereographic projection
+    Tube { radius: f32, sides: usize },
+}
+
+/// Fibers of the Hopf fibration over points of the 2-sphere, great circles on the 3-sphere of radius `r`
+///
+/// Every fiber gets the color of its base point, with the hue going around the poles and the lightness
+/// going up towards the north pole. Keep `r` below 2 for the stereographic projection, which looks from w = -2.
+pub fn hopf_fibers(base: &[Pos3D], res: usize, r: f32, style: FiberStyle) -> Object<Pos4D> {
+    let res = res.max(3);
+    let mut object = Object { cull: CullMode::None, ..Object::new(Vec::new(), Vec::new()) };
+
+    for &point in base {
+        let len = point.len();
+        if len == 0.0 {
+            continue;
+        }
+
+        let point = point / len;
+        let color = base_color(point);
+        let fiber = |t: f32| fiber_point(point, 2.0 * PI * t);
+
+        match style {
+            FiberStyle::Loop => {
+                let offset = object.nodes.len();
+
+                object.nodes.extend((0..res).map(|i| Node { pos: fiber(i as f32 / res as f32) * r, color, r: 0 }));
+                object.edges.extend((0..res).map(|i| Edge { node_a_index: offset + i, node_b_index: offset + (i + 1) % res, r: 10 }));
+            }
+            FiberStyle::Tube { radius, sides } => {
+                let angle = radius / r;
+
+                let tube = parametric_surface_4d(res, sides, (true, true), color, |u, v| {
+                    let p = fiber(u);
+                    let [h1, h2] = horizontal(p);
+                    let around = h1 * (2.0 * PI * v).cos() + h2 * (2.0 * PI * v).sin();
+
+                    // Moving along a great circle keeps the tube on the 3-sphere
+                    (p * angle.cos() + around * angle.sin()) * r
+                });
+
+                let offset = object.nodes.len();
+                object.nodes.extend(tube.nodes);
+                object.faces.extend(tube.faces.into_iter().map(|mut face| {
+                    face.node_a_index += offset;
+                    face.node_b_index += offset;
+                    face.node_c_index += offset;
+
+                    face
+                }));
+            }
+        }
+    }
+
+    object
+}
+
+/// Points on the unit 2-sphere along a curve, `f` is sampled `count` times between 0 and 1 and projected onto the sphere
+pub fn sample_curve<F>(count: usize, closed: bool, f: F) -> Vec<Pos3D>
+where
+    F: Fn(f32) -> Pos3D,
+{
+    // A closed curve ends where it started, so the last sample is left out
+    let segments = if closed { count } else { count.saturating_sub(1).max(1) };
+
+    (0..count).map(|i| f(i as f32 / segments as f32)).filter(|pos| pos.len() > 0.0).map(|pos| pos / pos.len()).collect()
+}
+
+/// Point of the fiber over a unit vector at angle `t` around it, on the unit 3-sphere
+///
+/// The fiber is every (z1, z2) with 2 z1 conj(z2) = x + iy and |z1|² - |z2|² = z.
+fn fiber_point(base: Pos3D, t: f32) -> Pos4D {
+    let xy = Complex::new(base.x, base.y);
+    let phase = Complex::from_polar(1.0, t);
+
+    // Divide by whichever of |z1| and |z2| is not close to 0
+    let (z1, z2) = if base.z >= 0.0 {
+        let a = ((1.0 + base.z) / 2.0).sqrt();
+
+        (phase * a, xy.conj() * phase * (0.5 / a))
+    } else {
+        let b = ((1.0 - base.z) / 2.0).sqrt();
+
+        (xy * phase * (0.5 / b), phase * b)
+    };
+
+    Pos4D { x: z1.re, y: z1.im, z: z2.re, w: z2.im }
+}
+
+/// Two unit vectors orthogonal to each other, to a point on the unit 3-sphere and to the fiber through it
+fn horizontal(p: Pos4D) -> [Pos4D; 2] {
+    [
+        Pos4D { x: -p.z, y: p.w, z: p.x, w: -p.y },
+        Pos4D { x: -p.w, y: -p.z, z: p.y, w: p.x },
+    ]
+}
+
+fn base_color(point: Pos3D) -> Color {
+    let hue = point.y.atan2(point.x).to_degrees().rem_euclid(360.0);
+    let lightness = 128.0 + point.z * 64.0;
+
+    Color::HSL(hue as u16, 255, lightness as u8)
+}
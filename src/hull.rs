@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use crate::{
+    object::{Cell, CullMode, Edge, Face, Node, Object},
+    pos::{Pos3D, Pos4D},
+    render::Color::*,
+};
+
+/// Smallest distance that counts, relative to the size of the point set
+const EPSILON: f64 = 1e-5;
+
+/// Convex hull of a 3D point set, with the faces wound outwards
+///
+/// Flat faces with more than three corners are split into triangles, their diagonals are left out of the edges.
+/// Points inside the hull or on its surface between corners are not part of the object.
+pub fn convex_hull_3d(points: Vec<Pos3D>) -> Result<Object<Pos3D>, &'static str> {
+    let coords: Vec<Vec<f64>> = points.iter().map(|pos| vec![pos.x as f64, pos.y as f64, pos.z as f64]).collect();
+    let hull = Hull::new(&coords)?;
+    let remap = hull.remap(points.len());
+
+    let faces = hull.facets.iter().map(|facet| {
+        let [a, b, c] = [0, 1, 2].map(|i| remap[facet.nodes[i]]);
+
+        // Facets are stored in any order, wind them so their normal points out
+        let [pa, pb, pc] = [0, 1, 2].map(|i| points[facet.nodes[i]]);
+        let normal = Pos3D { x: facet.normal[0] as f32, y: facet.normal[1] as f32, z: facet.normal[2] as f32 };
+
+        if ((pb - pa) ^ (pc - pa)) >> normal >= 0.0 {
+            Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 }
+        } else {
+            Face { node_a_index: a, node_b_index: c, node_c_index: b, r: 1 }
+        }
+    }).collect();
+
+    Ok(Object {
+        edges: hull.edges(&remap),
+        ..Object::new(hull.nodes(&points), faces)
+    })
+}
+
+/// Convex hull of a 4D point set, made of tetrahedral cells with their triangles and edges
+///
+/// Flat cells with more than four corners, like the octahedra of the 24-cell, are split into tetrahedra.
+/// The triangles inside them and the edges inside flat faces are left out.
+pub fn convex_hull_4d(points: Vec<Pos4D>) -> Result<Object<Pos4D>, &'static str> {
+    let coords: Vec<Vec<f64>> = points.iter().map(|pos| vec![pos.x as f64, pos.y as f64, pos.z as f64, pos.w as f64]).collect();
+    let hull = Hull::new(&coords)?;
+    let remap = hull.remap(points.len());
+
+    let cells = hull.facets.iter().map(|facet| {
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| remap[facet.nodes[i]]);
+
+        Cell { node_a_index: a, node_b_index: b, node_c_index: c, node_d_index: d }
+    }).collect();
+
+    // Triangles between two cells in the same hyperplane are inside a flat cell
+    let faces = hull.ridges().into_iter().filter(|(_, normals)| rank(normals) >= 2).map(|(ridge, _)| {
+        Face { node_a_index: remap[ridge[0]], node_b_index: remap[ridge[1]], node_c_index: remap[ridge[2]], r: 1 }
+    }).collect();
+
+    Ok(Object {
+        nodes: hull.nodes(&points),
+        edges: hull.edges(&remap),
+        faces,
+        cells,
+        normals: Vec::new(),
+        cull: CullMode::None,
+    })
+}
+
+/// Simplex on the surface of the hull with its outward unit normal and the distance of its hyperplane to the origin
+struct Facet {
+    nodes: Vec<usize>,
+    normal: Vec<f64>,
+    offset: f64,
+}
+
+impl Facet {
+    fn distance(&self, point: &[f64]) -> f64 {
+        dot(&self.normal, point) - self.offset
+    }
+}
+
+/// Incremental convex hull in any number of dimensions
+struct Hull {
+    facets: Vec<Facet>,
+}
+
+impl Hull {
+    fn new(points: &[Vec<f64>]) -> Result<Self, &'static str> {
+        let dimensions = points.first().ok_or("No points to build a hull of")?.len();
+        let size = points.iter().map(|point| len(&sub(point, &points[0]))).fold(0.0, f64::max);
+        let epsilon = size * EPSILON;
+
+        // Start from a simplex of points as far apart as possible, so it has a clear inside
+        let mut simplex = vec![0];
+        let mut basis: Vec<Vec<f64>> = Vec::new();
+        for _ in 0..dimensions {
+            let (index, residual) = points.iter().enumerate()
+                .map(|(index, point)| (index, orthogonalize(sub(point, &points[0]), &basis)))
+                .max_by(|(_, a), (_, b)| len(a).total_cmp(&len(b)))
+                .unwrap();
+
+            let length = len(&residual);
+            if length <= epsilon {
+                return Err("Points do not span every dimension");
+            }
+
+            simplex.push(index);
+            basis.push(scale(&residual, 1.0 / length));
+        }
+
+        let inside = scale(&simplex.iter().fold(vec![0.0; dimensions], |sum, &index| add(&sum, &points[index])), 1.0 / simplex.len() as f64);
+
+        let mut facets = Vec::new();
+        for skip in 0..simplex.len() {
+            let nodes = simplex.iter().enumerate().filter(|&(i, _)| i != skip).map(|(_, &index)| index).collect();
+
+            facets.push(facet(points, nodes, &inside, epsilon).ok_or("Degenerate facet")?);
+        }
+
+        for (index, point) in points.iter().enumerate() {
+            // Points inside or on the surface do not change the hull
+            if facets.iter().all(|facet| facet.distance(point) <= epsilon) {
+                continue;
+            }
+
+            // Facets the point lies on count as seen, so points in the hyperplane of a facet become corners
+            let (visible, hidden): (Vec<Facet>, Vec<Facet>) = facets.into_iter().partition(|facet| facet.distance(point) > -epsilon);
+            facets = hidden;
+
+            // Ridges of exactly one visible facet make up the horizon
+            let mut ridges: HashMap<Vec<usize>, usize> = HashMap::new();
+            for facet in visible.iter() {
+                for ridge in facet_ridges(&facet.nodes) {
+                    *ridges.entry(ridge).or_insert(0) += 1;
+                }
+            }
+
+            let mut horizon: Vec<Vec<usize>> = ridges.into_iter().filter(|&(_, count)| count == 1).map(|(ridge, _)| ridge).collect();
+            horizon.sort();
+
+            for mut nodes in horizon {
+                nodes.push(index);
+
+                facets.push(facet(points, nodes, &inside, epsilon).ok_or("Degenerate facet")?);
+            }
+        }
+
+        Ok(Self { facets })
+    }
+
+    /// Whether every point is a corner of the hull
+    fn used(&self, count: usize) -> Vec<bool> {
+        let mut used = vec![false; count];
+        for facet in self.facets.iter() {
+            for &node in facet.nodes.iter() {
+                used[node] = true;
+            }
+        }
+
+        used
+    }
+
+    /// New index of every point once the ones that are not corners are left out
+    fn remap(&self, count: usize) -> Vec<usize> {
+        self.used(count).into_iter().scan(0, |next, used| {
+            let index = *next;
+            if used { *next += 1; }
+
+            Some(index)
+        }).collect()
+    }
+
+    fn nodes<T: Copy>(&self, points: &[T]) -> Vec<Node<T>> {
+        points.iter().zip(self.used(points.len()))
+            .filter(|&(_, used)| used)
+            .map(|(&pos, _)| Node { pos, color: Purple, r: 0 })
+            .collect()
+    }
+
+    /// Every ridge with the normals of the facets around it
+    fn ridges(&self) -> Vec<(Vec<usize>, Vec<Vec<f64>>)> {
+        let mut ridges: HashMap<Vec<usize>, Vec<Vec<f64>>> = HashMap::new();
+        for facet in self.facets.iter() {
+            for ridge in facet_ridges(&facet.nodes) {
+                ridges.entry(ridge).or_default().push(facet.normal.clone());
+            }
+        }
+
+        let mut ridges: Vec<_> = ridges.into_iter().collect();
+        ridges.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        ridges
+    }
+
+    /// Edges of the polytope, the ones inside flat faces or cells have facet normals around them
+    /// that span less than all but one dimension
+    fn edges(&self, remap: &[usize]) -> Vec<Edge> {
+        let mut edges: HashMap<(usize, usize), Vec<Vec<f64>>> = HashMap::new();
+        for facet in self.facets.iter() {
+            for (i, &a) in facet.nodes.iter().enumerate() {
+                for &b in facet.nodes[i + 1..].iter() {
+                    edges.entry((a.min(b), a.max(b))).or_default().push(facet.normal.clone());
+                }
+            }
+        }
+
+        let dimensions = self.facets[0].normal.len();
+        let mut edges: Vec<(usize, usize)> = edges.into_iter().filter(|(_, normals)| rank(normals) >= dimensions - 1).map(|(edge, _)| edge).collect();
+        edges.sort();
+
+        edges.into_iter().map(|(a, b)| Edge { node_a_index: remap[a], node_b_index: remap[b], r: 5 }).collect()
+    }
+}
+
+/// Facet through the points, with its normal pointing away from `inside`
+///
+/// None if one of the points lies within `epsilon` of the space spanned by the ones before it.
+fn facet(points: &[Vec<f64>], mut nodes: Vec<usize>, inside: &[f64], epsilon: f64) -> Option<Facet> {
+    nodes.sort();
+
+    let origin = &points[nodes[0]];
+    let mut basis = Vec::new();
+    for &node in nodes[1..].iter() {
+        let residual = orthogonalize(sub(&points[node], origin), &basis);
+        let length = len(&residual);
+        if length <= epsilon {
+            return None;
+        }
+
+        basis.push(scale(&residual, 1.0 / length));
+    }
+
+    // The axis sticking out the most from the hyperplane gives the most accurate normal
+    let normal = (0..origin.len())
+        .map(|axis| orthogonalize((0..origin.len()).map(|i| if i == axis { 1.0 } else { 0.0 }).collect(), &basis))
+        .max_by(|a, b| len(a).total_cmp(&len(b)))?;
+    let mut normal = scale(&normal, 1.0 / len(&normal));
+
+    if dot(&normal, &sub(inside, origin)) > 0.0 {
+        normal = scale(&normal, -1.0);
+    }
+
+    Some(Facet { offset: dot(&normal, origin), normal, nodes })
+}
+
+/// Every facet with one node left out, in sorted order
+fn facet_ridges(nodes: &[usize]) -> impl Iterator<Item = Vec<usize>> + '_ {
+    (0..nodes.len()).map(move |skip| nodes.iter().enumerate().filter(|&(i, _)| i != skip).map(|(_, &node)| node).collect())
+}
+
+/// Number of directions the unit vectors span, ignoring tiny differences
+fn rank(vectors: &[Vec<f64>]) -> usize {
+    let mut basis: Vec<Vec<f64>> = Vec::new();
+    for vector in vectors {
+        let residual = orthogonalize(vector.clone(), &basis);
+        let length = len(&residual);
+
+        if length > 1e-3 {
+            basis.push(scale(&residual, 1.0 / length));
+        }
+    }
+
+    basis.len()
+}
+
+/// Remove the parts along an orthonormal basis
+fn orthogonalize(mut vector: Vec<f64>, basis: &[Vec<f64>]) -> Vec<f64> {
+    for direction in basis {
+        vector = sub(&vector, &scale(direction, dot(&vector, direction)));
+    }
+
+    vector
+}
+
+fn add(a: &[f64], b: &[f64]) -> Vec<f64> {
+    a.iter().zip(b).map(|(a, b)| a + b).collect()
+}
+
+fn sub(a: &[f64], b: &[f64]) -> Vec<f64> {
+    a.iter().zip(b).map(|(a, b)| a - b).collect()
+}
+
+fn scale(a: &[f64], factor: f64) -> Vec<f64> {
+    a.iter().map(|a| a * factor).collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn len(a: &[f64]) -> f64 {
+    dot(a, a).sqrt()
+}
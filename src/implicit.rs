@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use crate::{
+    object::{Cell, Face, Node, Object},
+    parametric::vertex_normals,
+    pos::{Pos3D, Pos4D},
+    render::Color,
+};
+
+/// Surface where `f` is 0, sampled on a grid of `res` cubes along every axis between `min` and `max`
+///
+/// Every cube is split into 6 tetrahedra, which needs no case table and has no ambiguous cases,
+/// at the cost of more triangles than marching cubes.
+///
+/// The inside is where `f` is negative, faces are wound to point out of it. A slice of a 4D function
+/// can be meshed by fixing w, like `|pos| f(Pos4D { w: 0.5, ..pos.into() })`.
+pub fn marching_tetrahedra<F>(f: F, min: Pos3D, max: Pos3D, res: usize, color: Color) -> Object<Pos3D>
+where
+    F: Fn(Pos3D) -> f32,
+{
+    let grid = Grid::sample(res, [min.x, min.y, min.z], [max.x, max.y, max.z], |[x, y, z]| f(Pos3D { x, y, z }));
+    let mut crossings = Crossings::default();
+    let mut faces = Vec::new();
+
+    let to_pos = |[x, y, z]: [f32; 3]| Pos3D { x, y, z };
+
+    grid.for_each_simplex(|inside, outside| {
+        let mut crossing = |a, b| crossings.get(&grid, a, b);
+
+        let polygon = match (inside.len(), outside.len()) {
+            (1, 3) => outside.iter().map(|&o| crossing(inside[0], o)).collect(),
+            (3, 1) => inside.iter().map(|&i| crossing(i, outside[0])).collect(),
+            (2, 2) => vec![crossing(inside[0], outside[0]), crossing(inside[0], outside[1]), crossing(inside[1], outside[1]), crossing(inside[1], outside[0])],
+            _ => Vec::new(),
+        };
+
+        // The surface faces from the inside corners towards the outside ones
+        let center = |corners: &[usize]| corners.iter().map(|&corner| to_pos(grid.point(corner))).sum::<Pos3D>() / corners.len() as f32;
+        let direction = center(outside) - center(inside);
+
+        for k in 1..polygon.len().saturating_sub(1) {
+            let [a, b, c] = [polygon[0], polygon[k], polygon[k + 1]];
+            let [pa, pb, pc] = [a, b, c].map(|node| to_pos(crossings.points[node]));
+
+            if ((pb - pa) ^ (pc - pa)) >> direction >= 0.0 {
+                faces.push(Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 });
+            } else {
+                faces.push(Face { node_a_index: a, node_b_index: c, node_c_index: b, r: 1 });
+            }
+        }
+    });
+
+    let nodes = crossings.points.into_iter().map(|point| Node { pos: to_pos(point), color, r: 0 }).collect();
+
+    let mut object = Object::new(nodes, faces);
+    object.weld(grid.step.iter().fold(f32::MAX, |min, &step| min.min(step)) * 1e-3);
+    object.normals = vertex_normals(&object);
+
+    object
+}
+
+/// Hypersurface where `f` is 0, sampled on a grid of `res` hypercubes along every axis between `min` and `max`
+///
+/// Every hypercube is split into 24 pentachora, which the hypersurface cuts in a tetrahedron or a triangular prism.
+/// Prisms are split into tetrahedra along the diagonals between the lowest and highest grid corners, so
+/// neighbouring pentachora agree on them.
+///
+/// Cells are oriented like the faces of `marching_tetrahedra`: the edges from their first corner to the other three,
+/// followed by the direction out of the inside, have a positive determinant. The triangles in `faces` are not wound.
+pub fn marching_pentachora<F>(f: F, min: Pos4D, max: Pos4D, res: usize, color: Color) -> Object<Pos4D>
+where
+    F: Fn(Pos4D) -> f32,
+{
+    let grid = Grid::sample(res, [min.x, min.y, min.z, min.w], [max.x, max.y, max.z, max.w], |[x, y, z, w]| f(Pos4D { x, y, z, w }));
+    let mut crossings = Crossings::default();
+    let mut cells = Vec::new();
+
+    grid.for_each_simplex(|inside, outside| {
+        let mut crossing = |a, b| crossings.get(&grid, a, b);
+        let mut pieces = Vec::new();
+        let mut cell = |corners: [usize; 4]| pieces.push(corners);
+
+        match (inside.len(), outside.len()) {
+            (1, 4) => cell([0, 1, 2, 3].map(|i| crossing(inside[0], outside[i]))),
+            (4, 1) => cell([0, 1, 2, 3].map(|i| crossing(inside[i], outside[0]))),
+            (2, 3) | (3, 2) => {
+                // A triangle of corners on one side times an edge of corners on the other
+                let (triangle, edge) = if inside.len() == 3 { (inside, outside) } else { (outside, inside) };
+                let mut p = |t: usize, e: usize| crossing(triangle[t], edge[e]);
+
+                let [x_a, y_a, z_a, x_b, y_b, z_b] = [p(0, 0), p(1, 0), p(2, 0), p(0, 1), p(1, 1), p(2, 1)];
+                cell([x_a, y_a, z_a, z_b]);
+                cell([x_a, y_a, y_b, z_b]);
+                cell([x_a, x_b, y_b, z_b]);
+            }
+            _ => {}
+        }
+
+        // The hypersurface faces from the inside corners towards the outside ones
+        let center = |corners: &[usize]| corners.iter().map(|&corner| grid.point(corner)).fold([0.0_f32; 4], |sum, point| {
+            std::array::from_fn(|axis| sum[axis] + point[axis] / corners.len() as f32)
+        });
+        let (from, to) = (center(inside), center(outside));
+        let direction = std::array::from_fn(|axis| to[axis] - from[axis]);
+
+        for [a, b, c, d] in pieces {
+            let [pa, pb, pc, pd] = [a, b, c, d].map(|node| crossings.points[node]);
+            let edge = |p: [f32; 4]| std::array::from_fn(|axis| p[axis] - pa[axis]);
+
+            let [c, d] = if determinant([edge(pb), edge(pc), edge(pd), direction]) >= 0.0 { [c, d] } else { [d, c] };
+            cells.push(Cell { node_a_index: a, node_b_index: b, node_c_index: c, node_d_index: d });
+        }
+    });
+
+    let nodes = crossings.points.into_iter().map(|[x, y, z, w]| Node { pos: Pos4D { x, y, z, w }, color, r: 0 }).collect();
+
+    // Crossings on grid corners where `f` is exactly 0 collapse some of the cells
+    let mut object = Object { cells, ..Object::new(nodes, Vec::new()) };
+    object.weld(grid.step.iter().fold(f32::MAX, |min, &step| min.min(step)) * 1e-3);
+
+    Object::from_cells(object.nodes, object.cells, 5)
+}
+
+/// Samples of a function on the corners of a regular grid, indexed with the first axis changing fastest
+struct Grid<const N: usize> {
+    res: usize,
+    min: [f32; N],
+    step: [f32; N],
+    values: Vec<f32>,
+}
+
+impl<const N: usize> Grid<N> {
+    fn sample<F>(res: usize, min: [f32; N], max: [f32; N], f: F) -> Self
+    where
+        F: Fn([f32; N]) -> f32,
+    {
+        let res = res.max(1);
+        let step = std::array::from_fn(|axis| (max[axis] - min[axis]) / res as f32);
+
+        let mut grid = Self { res, min, step, values: Vec::new() };
+        grid.values = (0..(res + 1).pow(N as u32)).map(|index| f(grid.point(index))).collect();
+
+        grid
+    }
+
+    fn coords(&self, mut index: usize) -> [usize; N] {
+        std::array::from_fn(|_| {
+            let coord = index % (self.res + 1);
+            index /= self.res + 1;
+
+            coord
+        })
+    }
+
+    fn point(&self, index: usize) -> [f32; N] {
+        let coords = self.coords(index);
+
+        std::array::from_fn(|axis| self.min[axis] + coords[axis] as f32 * self.step[axis])
+    }
+
+    /// Call `f` with the inside and outside corners of every simplex the surface goes through, sorted by index
+    ///
+    /// Every cube is split into one simplex per order of walking along the axes from its lowest to its highest
+    /// corner, which line up between neighbouring cubes.
+    fn for_each_simplex<F>(&self, mut f: F)
+    where
+        F: FnMut(&[usize], &[usize]),
+    {
+        let strides: [usize; N] = std::array::from_fn(|axis| (self.res + 1).pow(axis as u32));
+        let orders = permutations(N);
+
+        for cube in 0..self.res.pow(N as u32) {
+            let mut rest = cube;
+            let origin: usize = (0..N).map(|axis| {
+                let coord = rest % self.res;
+                rest /= self.res;
+
+                coord * strides[axis]
+            }).sum();
+
+            // Cubes entirely inside or outside have nothing to mesh
+            let corners = (0..1 << N).map(|bits| origin + (0..N).filter(|axis| bits & (1 << axis) != 0).map(|axis| strides[axis]).sum::<usize>());
+            let inside_count = corners.filter(|&corner| self.values[corner] < 0.0).count();
+            if inside_count == 0 || inside_count == 1 << N {
+                continue;
+            }
+
+            for order in orders.iter() {
+                let mut corner = origin;
+                let (mut inside, mut outside) = (Vec::with_capacity(N + 1), Vec::with_capacity(N + 1));
+
+                for step in 0..=N {
+                    if step > 0 {
+                        corner += strides[order[step - 1]];
+                    }
+
+                    if self.values[corner] < 0.0 { inside.push(corner) } else { outside.push(corner) }
+                }
+
+                if !inside.is_empty() && !outside.is_empty() {
+                    f(&inside, &outside);
+                }
+            }
+        }
+    }
+}
+
+/// Points where the surface crosses grid edges, each one shared by every simplex around the edge
+struct Crossings<const N: usize> {
+    indices: HashMap<(usize, usize), usize>,
+    points: Vec<[f32; N]>,
+}
+
+impl<const N: usize> Default for Crossings<N> {
+    fn default() -> Self {
+        Self { indices: HashMap::new(), points: Vec::new() }
+    }
+}
+
+impl<const N: usize> Crossings<N> {
+    /// Index of the point between an inside and an outside corner where `f` is 0 when interpolated linearly
+    fn get(&mut self, grid: &Grid<N>, inside: usize, outside: usize) -> usize {
+        *self.indices.entry((inside, outside)).or_insert_with(|| {
+            let (a, b) = (grid.values[inside], grid.values[outside]);
+            let t = a / (a - b);
+            let (from, to) = (grid.point(inside), grid.point(outside));
+
+            self.points.push(std::array::from_fn(|axis| from[axis] + (to[axis] - from[axis]) * t));
+            self.points.len() - 1
+        })
+    }
+}
+
+/// Determinant of the 4x4 matrix with the given rows, expanded along the first row
+fn determinant(rows: [[f32; 4]; 4]) -> f32 {
+    (0..4).map(|column| {
+        // The 3x3 minor without the first row and this column
+        let minor: Vec<[f32; 3]> = rows[1..].iter().map(|row| {
+            let mut rest = row.iter().enumerate().filter(|&(i, _)| i != column).map(|(_, &v)| v);
+
+            [rest.next().unwrap(), rest.next().unwrap(), rest.next().unwrap()]
+        }).collect();
+
+        let [a, b, c] = [minor[0], minor[1], minor[2]];
+        let minor = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0]) + a[2] * (b[0] * c[1] - b[1] * c[0]);
+        let sign = if column % 2 == 0 { 1.0 } else { -1.0 };
+
+        sign * rows[0][column] * minor
+    }).sum()
+}
+
+/// Every order of the numbers below `n`
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+
+    permutations(n - 1).into_iter().flat_map(|order| {
+        (0..n).map(move |position| {
+            let mut order = order.clone();
+            order.insert(position, n - 1);
+
+            order
+        })
+    }).collect()
+}
@@ -1,14 +1,31 @@
-use std::{collections::{HashMap, HashSet}, isize};
+use std::collections::{HashMap, HashSet};
 
 use pos::Empty;
-use object::{Node, Face, Object};
+use object::{Cell, Edge, Node, Face, Object};
 use render::{Color, Screen};
 
+pub mod animation;
+pub mod colormap;
+pub mod complex;
+pub mod depth_cue;
+pub mod export;
+pub mod fractal;
+pub mod hopf;
+pub mod hull;
+pub mod implicit;
 pub mod matrix;
+pub mod mesh;
+pub mod operators;
+pub mod parametric;
 pub mod pos;
 pub mod projection;
+pub mod quaternion;
 pub mod render;
+pub mod rotor;
+pub mod sdf;
 pub mod shapes;
+pub mod svg;
+pub mod terminal;
 pub mod transform;
 pub mod object;
 
@@ -33,18 +50,26 @@ pub fn print_point(
     }
 }
 
-pub fn remove_duplicates<T>(object: Object<T>) -> Object<T> where Node<T>: Eq + PartialEq + Clone, T: std::hash::Hash + Empty {
+/// Merge identical nodes, positions are compared truncated to 1/100 units, see Object::weld to merge by distance
+pub fn remove_duplicates<T>(object: Object<T>) -> Object<T> where Node<T>: Eq + PartialEq + Clone, T: std::hash::Hash + Empty + Copy {
     let mut unique_nodes: HashMap<Node<T>, usize> = HashMap::new();
 
     let mut remapped_faces: HashSet<Face> = HashSet::new();
 
     // Create a hashmap to store the remap between indices
     let mut nodes_remap: HashMap<usize, usize> = HashMap::new();
+
+    // Normals of the first node of every group
+    let mut normals = Vec::new();
     
     object.nodes.into_iter().enumerate().for_each(|(orig_index, node)| {
         let new_index = if let Some(&index) = unique_nodes.get(&node) {
             index
         } else {
+            if let Some(&normal) = object.normals.get(orig_index) {
+                normals.push(normal);
+            }
+
             unique_nodes.insert(node, unique_nodes.len());
             unique_nodes.len() - 1
         };
@@ -66,9 +91,8 @@ pub fn remove_duplicates<T>(object: Object<T>) -> Object<T> where Node<T>: Eq +
             face.node_c_index = new_index;
         }
 
-        if face.node_a_index >= unique_nodes.len() || face.node_b_index >= unique_nodes.len() || face.node_c_index >= unique_nodes.len() {
-            println!("{:?}", face)
-        } else {
+        // Faces pointing outside the object are dropped, Object::validate reports them
+        if face.node_a_index < unique_nodes.len() && face.node_b_index < unique_nodes.len() && face.node_c_index < unique_nodes.len() {
             remapped_faces.insert(face);
         }
     }
@@ -81,5 +105,15 @@ pub fn remove_duplicates<T>(object: Object<T>) -> Object<T> where Node<T>: Eq +
         unique_nodes_ord[index] = node;
     });
 
-    Object { nodes: unique_nodes_ord, faces: remapped_faces.drain().collect() }
+    // Edges and cells only need their indices remapped
+    let remap = |index: usize| nodes_remap.get(&index).copied().unwrap_or(index);
+    let edges = object.edges.iter().map(|edge| Edge { node_a_index: remap(edge.node_a_index), node_b_index: remap(edge.node_b_index), r: edge.r }).collect();
+    let cells = object.cells.iter().map(|cell| Cell {
+        node_a_index: remap(cell.node_a_index),
+        node_b_index: remap(cell.node_b_index),
+        node_c_index: remap(cell.node_c_index),
+        node_d_index: remap(cell.node_d_index),
+    }).collect();
+
+    Object { nodes: unique_nodes_ord, edges, faces: remapped_faces.drain().collect(), cells, normals, cull: object.cull }
 }
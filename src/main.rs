@@ -1,23 +1,133 @@
-use std::{f32::consts::PI, sync::{Arc, Mutex}};
+use std::{env, f32::consts::PI, io::{self, Write}, path::PathBuf, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+// Crates for terminal managment
+use crossterm::{
+    cursor,
+    event::{self, KeyCode, KeyEventKind, KeyModifiers},
+    execute, queue, terminal,
+};
 
 // Crates for window managment
 use pixels::{PixelsBuilder, SurfaceTexture};
 use winit::{
     dpi::PhysicalSize,
-    event::{Event, WindowEvent},
+    event::{ElementState, Event, KeyEvent, WindowEvent},
+    keyboard::Key,
     event_loop::{EventLoop, ControlFlow},
     window::WindowBuilder, error::EventLoopError,
 };
 
 // Actual rendering code
-use n_renderer::{pos::{RotationAxis, RotationPlane}, projection::{Projection, ProjectionType::{self, *}}, render::Screen, shapes::*, transform::*};
+use n_renderer::{animation::{Animation, Keyframe, Playback}, colormap::Gradient, depth_cue::{DepthCue, Fog}, export::{export, render_animation, write_depth_pfm, write_depth_png, write_png, ExportFormat}, object::Object, pos::{Pos4D, RotationPlane}, projection::ProjectionType, render::{Color, Filter, Screen}, rotor::Rotor, sdf::{MarchMode, RayMarcher, Sdf}, shapes::*, svg::write_svg, terminal::{to_ansi, TerminalMode}};
 
 const WIDTH: usize = 600;
 const HEIGHT: usize = 600;
 
 const SCALE: f32 = 0.7;
 
+// Samples per pixel along each axis for anti-aliasing
+const SAMPLES: usize = 2;
+
 fn main() -> Result<(), EventLoopError> {
+    // One full turn in the WX plane every 30 seconds, split into thirds so every step is the shortest path
+    let animation = Animation::new(
+        (0..=3).map(|i| Keyframe {
+            rotation: Rotor::from_plane(RotationPlane::WX.into(), 2.0 * PI / 3.0 * i as f32),
+            projection_scale: 0.5 / SCALE,
+            ..Keyframe::new(10.0 * i as f32)
+        }).collect(),
+        Playback::Loop,
+    );
+
+    // let shape = create_3_cube(0.5);
+    // let shape = create_4_cube(1.0);
+    // let mut shape = create_3_sphere(1000);
+    let shape = create_hypersphere(1, 1.8);
+    // let shape = create_duoprism(6, 4, 1.5);
+    // let shape = create_complex_sqrt(40, 1.5);
+    // let shape = create_hopf_fibration(48, 1.5);
+    // let shape = create_quaternion_julia(40, 12);
+    // let mut shape = create_torus(100, 1.8);
+    // let mut shape = empty();
+
+    // Render to a file instead of a window: --export <file.gif|file.apng|directory> [--frames N] [--fps F] [--samples S]
+    let args: Vec<String> = env::args().collect();
+    let arg = |name: &str| args.iter().position(|a| a == name).and_then(|i| args.get(i + 1));
+
+    if let Some(path) = arg("--export") {
+        let path = PathBuf::from(path);
+        let frames = arg("--frames").and_then(|v| v.parse().ok()).unwrap_or(120);
+        let fps: f32 = arg("--fps").and_then(|v| v.parse().ok()).unwrap_or(30.0);
+        let samples = arg("--samples").and_then(|v| v.parse().ok()).unwrap_or(SAMPLES);
+
+        let screens = render_animation(&shape, &animation, ProjectionType::Stereographic, (WIDTH, HEIGHT), samples, frames, 1.0 / fps);
+
+        if let Err(e) = export(&screens, ExportFormat::from_path(&path), &path, 1.0 / fps) {
+            println!("Export failed: {}", e);
+        }
+
+        return Ok(());
+    }
+
+    // Write the first frame as a vector image: --svg <file.svg>
+    if let Some(path) = arg("--svg") {
+        let frame = animation.sample(0.0);
+
+        if let Err(e) = write_svg(&frame.apply(&shape), (WIDTH, HEIGHT), frame.projection(ProjectionType::Stereographic), &PathBuf::from(path)) {
+            println!("Export failed: {}", e);
+        }
+
+        return Ok(());
+    }
+
+    // Write the depth buffer of the first frame: --depth <file.png|file.pfm>
+    if let Some(path) = arg("--depth") {
+        let path = PathBuf::from(path);
+        let screens = render_animation(&shape, &animation, ProjectionType::Stereographic, (WIDTH, HEIGHT), SAMPLES, 1, 0.0);
+
+        let result = match path.extension().and_then(|e| e.to_str()) {
+            Some("pfm") => write_depth_pfm(&screens[0], &path),
+            _ => write_depth_png(&screens[0], &path),
+        };
+
+        if let Err(e) = result {
+            println!("Export failed: {}", e);
+        }
+
+        return Ok(());
+    }
+
+    // Ray march a smooth scene instead of drawing the shape: --raymarch <file.png> [--slice W]
+    if let Some(path) = arg("--raymarch") {
+        let scene = Sdf::SmoothUnion(
+            Box::new(Sdf::Tesseract { center: Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 0.0 }, r: 0.5 }.rotated(animation.sample(2.0).rotation)),
+            Box::new(Sdf::CliffordTorus { r: 1.3, thickness: 0.12 }),
+            0.2,
+        );
+
+        // Slice at the given w, or look at the whole scene from a 4D eye
+        let mode = arg("--slice").and_then(|v| v.parse().ok()).map_or(MarchMode::Projection, MarchMode::Slice);
+
+        let mut screen = Screen::with_supersampling(WIDTH, HEIGHT, SAMPLES, Filter::Tent);
+        RayMarcher { bounds: 1.6, ..RayMarcher::new(mode, 1.0) }.render(&scene, &mut screen);
+        screen.resolve();
+
+        if let Err(e) = write_png(&screen, &PathBuf::from(path)) {
+            println!("Export failed: {}", e);
+        }
+
+        return Ok(());
+    }
+
+    // Animate in the terminal instead of a window: --tty
+    if args.iter().any(|a| a == "--tty") {
+        if let Err(e) = run_tty(&shape, &animation) {
+            println!("Terminal rendering failed: {}", e);
+        }
+
+        return Ok(());
+    }
+
     let event_loop = EventLoop::new().unwrap();
 
     // Initialise the window
@@ -41,16 +151,18 @@ fn main() -> Result<(), EventLoopError> {
     let mut pixels: pixels::Pixels = PixelsBuilder::new(WIDTH as u32, HEIGHT as u32, surface_texture).build().unwrap();
     
     // Create a pixelbuffer
-    let screen = Arc::new(Mutex::new(Screen::new(WIDTH, HEIGHT)));
+    let screen = Arc::new(Mutex::new(Screen::with_supersampling(WIDTH, HEIGHT, SAMPLES, Filter::Tent)));
 
-    let mut t: f32 = 0.0;
+    // Color the nodes by their w coordinate and fade out the far side of the object
+    let depth_cue = DepthCue {
+        fog: Some(Fog::new(0.5, 2.0)),
+        ..DepthCue::new(Gradient::evenly_spaced(&[Color::Red, Color::Purple, Color::Blue]))
+    };
 
-    // let shape = create_3_cube(0.5);
-    // let shape = create_4_cube(1.0);
-    // let mut shape = create_3_sphere(1000);
-    let shape = create_4_sphere(1000, 1.8);
-    // let mut shape = create_torus(100, 1.8);
-    // let mut shape = empty();
+    // Show the depth buffer instead of the colors, toggled with z
+    let mut show_depth = false;
+
+    let start = Instant::now();
 
     event_loop.set_control_flow(ControlFlow::Poll);
 
@@ -71,6 +183,12 @@ fn main() -> Result<(), EventLoopError> {
                 // let _ = pixels.resize_buffer(new_size.width, new_size.height);
                 // let _ = pixels.resize_surface(new_size.width, new_size.height);
             },
+            Event::WindowEvent {
+                event: WindowEvent::KeyboardInput { event: KeyEvent { logical_key: Key::Character(key), state: ElementState::Pressed, repeat: false, .. }, .. },
+                ..
+            } if key.as_str() == "z" => {
+                show_depth = !show_depth;
+            },
             Event::AboutToWait => {
                 window.request_redraw();
             },
@@ -83,18 +201,23 @@ fn main() -> Result<(), EventLoopError> {
                     screen_lock.clear();
                 }
 
-                t += 0.1;
+                let frame = animation.sample(start.elapsed().as_secs_f32());
 
                 // Transform the object
-                let rotated_shape = shape.rotate(RotationPlane::get_rot_mat_4d(RotationPlane::WX, PI / 16.0 * t));
+                let rotated_shape = depth_cue.apply(&frame.apply(&shape));
 
                 // Draw the object
-                rotated_shape.draw(Arc::clone(&screen), Projection::new(ProjectionType::Stereographic, 0.5 / SCALE));
+                rotated_shape.draw(Arc::clone(&screen), frame.projection(ProjectionType::Stereographic));
 
                 {
-                    let screen_lock = screen.lock().unwrap();
-                    let screen_slice = screen_lock.get_slice();
-                    pixels.frame_mut().copy_from_slice(screen_slice);
+                    let mut screen_lock = screen.lock().unwrap();
+                    screen_lock.resolve();
+
+                    if show_depth {
+                        pixels.frame_mut().copy_from_slice(&screen_lock.depth_to_rgba());
+                    } else {
+                        pixels.frame_mut().copy_from_slice(screen_lock.get_slice());
+                    }
                 }
                                 // Display the result on the screen
                 if pixels
@@ -109,3 +232,90 @@ fn main() -> Result<(), EventLoopError> {
         }
     })
 }
+
+/// Render the animation into the terminal until q, escape or ctrl-c is pressed
+///
+/// The arrow keys rotate in the XZ and YZ planes, w/s and a/d in the XW and YW planes,
+/// space pauses the animation, b switches between half blocks and braille and r resets the rotation.
+fn run_tty(shape: &Object<Pos4D>, animation: &Animation) -> io::Result<()> {
+    let mut stdout = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+    // Restore the terminal even if rendering fails
+    let result = tty_loop(&mut stdout, shape, animation);
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn tty_loop(stdout: &mut io::Stdout, shape: &Object<Pos4D>, animation: &Animation) -> io::Result<()> {
+    let step = PI / 32.0;
+    let mut manual = Rotor::identity();
+    let mut mode = TerminalMode::HalfBlock;
+
+    let mut time = 0.0;
+    let mut paused = false;
+    let mut last_frame = Instant::now();
+
+    'render: loop {
+        // Handle all key presses that arrived since the last frame
+        while event::poll(Duration::from_millis(30))? {
+            if let event::Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Release { continue; }
+
+                let plane = match key.code {
+                    KeyCode::Left => Some((RotationPlane::XZ, -step)),
+                    KeyCode::Right => Some((RotationPlane::XZ, step)),
+                    KeyCode::Up => Some((RotationPlane::YZ, -step)),
+                    KeyCode::Down => Some((RotationPlane::YZ, step)),
+                    KeyCode::Char('w') => Some((RotationPlane::XW, step)),
+                    KeyCode::Char('s') => Some((RotationPlane::XW, -step)),
+                    KeyCode::Char('a') => Some((RotationPlane::YW, -step)),
+                    KeyCode::Char('d') => Some((RotationPlane::YW, step)),
+                    _ => None,
+                };
+
+                if let Some((plane, angle)) = plane {
+                    manual = (Rotor::from_plane(plane.into(), angle) * manual).normalize();
+                }
+
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break 'render,
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break 'render,
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Char('r') => manual = Rotor::identity(),
+                    KeyCode::Char('b') => mode = match mode {
+                        TerminalMode::HalfBlock => TerminalMode::Braille,
+                        TerminalMode::Braille => TerminalMode::HalfBlock,
+                    },
+                    _ => (),
+                }
+            }
+        }
+
+        // Advance the animation using the wall clock
+        if !paused {
+            time += last_frame.elapsed().as_secs_f32();
+        }
+        last_frame = Instant::now();
+
+        let frame = animation.sample(time);
+        let frame = Keyframe { rotation: manual * frame.rotation, ..frame };
+
+        let screen = Arc::new(Mutex::new(Screen::new(WIDTH, HEIGHT)));
+        frame.apply(shape).draw(Arc::clone(&screen), frame.projection(ProjectionType::Stereographic));
+
+        let (columns, rows) = terminal::size()?;
+        let text = to_ansi(&screen.lock().unwrap(), columns as usize, rows as usize, mode);
+
+        queue!(stdout, cursor::MoveTo(0, 0))?;
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,373 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::{object::{Cell, CullMode, Edge, Face, Node, Object}, pos::{Len, Pos4D}, rotor::Bivector};
+
+/// Problem found in the faces and nodes of an object
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Face refers to a node that does not exist
+    IndexOutOfRange { face: usize, index: usize },
+    /// Face uses a node more than once or its nodes lie on a line
+    DegenerateFace { face: usize },
+    /// Face uses the same nodes as an earlier face, in any order
+    DuplicateFace { face: usize, original: usize },
+    /// Edge shared by more than two faces
+    NonManifoldEdge { edge: (usize, usize), faces: Vec<usize> },
+    /// Face runs along a shared edge in the same direction as an earlier face
+    InconsistentWinding { face: usize },
+    /// Node not used by any face, expected for point clouds
+    UnreferencedNode { node: usize },
+}
+
+impl Face {
+    fn indices(&self) -> [usize; 3] {
+        [self.node_a_index, self.node_b_index, self.node_c_index]
+    }
+
+    /// Reverse the winding of the face
+    pub fn flip(&mut self) {
+        std::mem::swap(&mut self.node_b_index, &mut self.node_c_index);
+    }
+}
+
+impl Edge {
+    fn indices(&self) -> [usize; 2] {
+        [self.node_a_index, self.node_b_index]
+    }
+}
+
+impl Cell {
+    fn indices(&self) -> [usize; 4] {
+        [self.node_a_index, self.node_b_index, self.node_c_index, self.node_d_index]
+    }
+}
+
+impl<T> Object<T> {
+    /// Object made of tetrahedra, with every edge and triangle of the cells once and duplicate cells removed
+    ///
+    /// Edges get radius `edge_r`, with 0 only the faces are drawn. Faces are not culled since their winding is arbitrary.
+    pub fn from_cells(nodes: Vec<Node<T>>, cells: Vec<Cell>, edge_r: usize) -> Self {
+        let mut unique = BTreeSet::new();
+        let cells: Vec<Cell> = cells.into_iter().filter(|cell| {
+            let mut key = cell.indices();
+            key.sort();
+
+            unique.insert(key)
+        }).collect();
+
+        // Every edge and triangle of the cells once, in a fixed order
+        let mut edges = BTreeSet::new();
+        let mut faces = BTreeSet::new();
+        for [a, b, c, d] in unique {
+            edges.extend([(a, b), (a, c), (a, d), (b, c), (b, d), (c, d)]);
+            faces.extend([(a, b, c), (a, b, d), (a, c, d), (b, c, d)]);
+        }
+
+        Object {
+            nodes,
+            edges: edges.into_iter().map(|(a, b)| Edge { node_a_index: a, node_b_index: b, r: edge_r }).collect(),
+            faces: faces.into_iter().map(|(a, b, c)| Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 }).collect(),
+            cells,
+            normals: Vec::new(),
+            cull: CullMode::None,
+        }
+    }
+}
+
+impl<T> Object<T> where T: Into<Pos4D> + Copy {
+    /// Check the faces and nodes for problems, ordered by kind and then by index
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        // Faces that can be checked further
+        let mut valid = Vec::new();
+
+        for (index, face) in self.faces.iter().enumerate() {
+            if let Some(&node) = face.indices().iter().find(|&&node| node >= self.nodes.len()) {
+                diagnostics.push(Diagnostic::IndexOutOfRange { face: index, index: node });
+            } else if self.is_degenerate(face) {
+                diagnostics.push(Diagnostic::DegenerateFace { face: index });
+            } else {
+                valid.push(index);
+            }
+        }
+
+        // Faces with the same set of nodes
+        let mut seen: HashMap<[usize; 3], usize> = HashMap::new();
+        valid.retain(|&index| {
+            let mut key = self.faces[index].indices();
+            key.sort();
+
+            match seen.get(&key) {
+                Some(&original) => {
+                    diagnostics.push(Diagnostic::DuplicateFace { face: index, original });
+                    false
+                },
+                None => {
+                    seen.insert(key, index);
+                    true
+                },
+            }
+        });
+
+        let edges = self.edge_faces(&valid);
+
+        let mut non_manifold: Vec<_> = edges.iter().filter(|(_, faces)| faces.len() > 2).collect();
+        non_manifold.sort();
+        for (&edge, faces) in non_manifold {
+            diagnostics.push(Diagnostic::NonManifoldEdge { edge, faces: faces.clone() });
+        }
+
+        for face in self.winding_conflicts(valid.iter().copied()) {
+            diagnostics.push(Diagnostic::InconsistentWinding { face });
+        }
+
+        let mut referenced = vec![false; self.nodes.len()];
+        for node in self.referenced_nodes() {
+            if let Some(r) = referenced.get_mut(node) {
+                *r = true;
+            }
+        }
+
+        for (node, _) in referenced.iter().enumerate().filter(|(_, &r)| !r) {
+            diagnostics.push(Diagnostic::UnreferencedNode { node });
+        }
+
+        diagnostics
+    }
+
+    /// Fix the problems that can be fixed without changing what the object looks like, returns the remaining problems
+    ///
+    /// Removes out of range, degenerate and duplicate faces, flips faces to match the winding of their neighbours
+    /// and removes unreferenced nodes that are not drawn. Non-manifold edges are left as is.
+    pub fn repair(&mut self) -> Vec<Diagnostic> {
+        // Drop faces that can not be drawn or are drawn twice
+        let mut seen: HashMap<[usize; 3], usize> = HashMap::new();
+        let faces: Vec<Face> = self.faces.iter().copied().filter(|face| {
+            if face.indices().iter().any(|&node| node >= self.nodes.len()) || self.is_degenerate(face) {
+                return false;
+            }
+
+            let mut key = face.indices();
+            key.sort();
+
+            seen.insert(key, 0).is_none()
+        }).collect();
+        self.faces = faces;
+
+        self.orient_faces();
+
+        // Remove nodes that are neither drawn nor used, keeping the order of the others
+        // Edges and cells pointing outside the object can not be kept either
+        let len = self.nodes.len();
+        self.edges.retain(|edge| edge.indices().iter().all(|&node| node < len));
+        self.cells.retain(|cell| cell.indices().iter().all(|&node| node < len));
+
+        let mut referenced = vec![false; self.nodes.len()];
+        for node in self.referenced_nodes() {
+            referenced[node] = true;
+        }
+
+        let mut remap = vec![0; self.nodes.len()];
+        let mut kept = 0;
+        for (index, node) in self.nodes.iter().enumerate() {
+            remap[index] = kept;
+
+            if referenced[index] || node.r != 0 {
+                referenced[index] = true;
+                kept += 1;
+            }
+        }
+
+        let mut index = 0;
+        self.nodes.retain(|_| {
+            index += 1;
+            referenced[index - 1]
+        });
+
+        if self.normals.len() == referenced.len() {
+            let mut index = 0;
+            self.normals.retain(|_| {
+                index += 1;
+                referenced[index - 1]
+            });
+        }
+
+        self.remap_nodes(|index| Some(remap[index]));
+
+        self.validate()
+    }
+
+    /// Merge nodes closer than `epsilon` to each other, keeping the first node of every group
+    ///
+    /// Nodes are compared by position only, the merged node keeps the color and radius of the first one.
+    /// The remaining nodes stay in their original order. Edges, faces and cells that collapse are removed,
+    /// like the ones that end up on the same nodes as an earlier one, in any order.
+    pub fn weld(&mut self, epsilon: f32) {
+        // Cells as large as epsilon, so every node within epsilon is in one of the neighbouring cells
+        let cell_size = if epsilon > 0.0 { epsilon } else { 1.0 };
+        let cell = |pos: Pos4D| [pos.x, pos.y, pos.z, pos.w].map(|c| (c / cell_size).floor() as i64);
+
+        let mut grid: HashMap<[i64; 4], Vec<usize>> = HashMap::new();
+        let mut remap: Vec<usize> = Vec::with_capacity(self.nodes.len());
+        // Original indices of the nodes that stay
+        let mut kept: Vec<usize> = Vec::new();
+
+        for node in self.nodes.iter() {
+            let pos: Pos4D = node.pos.into();
+            let [x, y, z, w] = cell(pos);
+
+            // Closest kept node in the 3x3x3x3 block of cells, the earliest one on ties
+            let mut closest: Option<(f32, usize)> = None;
+            for offset in 0..81 {
+                let key = [x + offset % 3 - 1, y + offset / 3 % 3 - 1, z + offset / 9 % 3 - 1, w + offset / 27 - 1];
+
+                for &index in grid.get(&key).into_iter().flatten() {
+                    let distance = (self.nodes[kept[index]].pos.into() - pos).len();
+
+                    if distance <= epsilon && closest.is_none_or(|(d, i)| (distance, index) < (d, i)) {
+                        closest = Some((distance, index));
+                    }
+                }
+            }
+
+            match closest {
+                Some((_, index)) => remap.push(index),
+                None => {
+                    grid.entry([x, y, z, w]).or_default().push(kept.len());
+                    remap.push(kept.len());
+                    kept.push(remap.len() - 1);
+                },
+            }
+        }
+
+        self.nodes = kept.iter().map(|&index| self.nodes[index]).collect();
+        self.normals = kept.iter().filter_map(|&index| self.normals.get(index).copied()).collect();
+
+        self.remap_nodes(|index| remap.get(index).copied());
+
+        // Merged nodes can make different edges, faces and cells the same
+        let mut seen = BTreeSet::new();
+        self.edges.retain(|edge| {
+            let mut key = edge.indices().to_vec();
+            key.sort();
+
+            seen.insert(key)
+        });
+        self.faces.retain(|face| {
+            let mut key = face.indices().to_vec();
+            key.sort();
+
+            seen.insert(key)
+        });
+        self.cells.retain(|cell| {
+            let mut key = cell.indices().to_vec();
+            key.sort();
+
+            seen.insert(key)
+        });
+    }
+
+    /// Indices of all nodes used by edges, faces and cells
+    fn referenced_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        let edges = self.edges.iter().flat_map(|edge| edge.indices());
+        let faces = self.faces.iter().flat_map(|face| face.indices());
+        let cells = self.cells.iter().flat_map(|cell| cell.indices());
+
+        edges.chain(faces).chain(cells)
+    }
+
+    /// Replace the node indices of edges, faces and cells, removing the ones without a new index or that collapse
+    fn remap_nodes(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        let distinct = |indices: &[usize]| (1..indices.len()).all(|i| !indices[..i].contains(&indices[i]));
+
+        self.edges = self.edges.iter().filter_map(|edge| {
+            let [a, b] = edge.indices().map(&remap);
+            let indices = [a?, b?];
+
+            distinct(&indices).then_some(Edge { node_a_index: indices[0], node_b_index: indices[1], r: edge.r })
+        }).collect();
+
+        self.faces = self.faces.iter().filter_map(|face| {
+            let [a, b, c] = face.indices().map(&remap);
+            let indices = [a?, b?, c?];
+
+            distinct(&indices).then_some(Face { node_a_index: indices[0], node_b_index: indices[1], node_c_index: indices[2], r: face.r })
+        }).collect();
+
+        self.cells = self.cells.iter().filter_map(|cell| {
+            let [a, b, c, d] = cell.indices().map(&remap);
+            let indices = [a?, b?, c?, d?];
+
+            distinct(&indices).then_some(Cell { node_a_index: indices[0], node_b_index: indices[1], node_c_index: indices[2], node_d_index: indices[3] })
+        }).collect();
+    }
+
+    /// Flip faces so neighbours across manifold edges agree with the first face of every connected part
+    fn orient_faces(&mut self) {
+        let all: Vec<usize> = (0..self.faces.len()).collect();
+        let edges = self.edge_faces(&all);
+
+        let mut visited = vec![false; self.faces.len()];
+        let mut queue = VecDeque::new();
+
+        for seed in 0..self.faces.len() {
+            if visited[seed] { continue; }
+
+            visited[seed] = true;
+            queue.push_back(seed);
+
+            while let Some(index) = queue.pop_front() {
+                let [a, b, c] = self.faces[index].indices();
+
+                for (from, to) in [(a, b), (b, c), (c, a)] {
+                    let neighbours = &edges[&(from.min(to), from.max(to))];
+                    if neighbours.len() != 2 { continue; }
+
+                    let neighbour = if neighbours[0] == index { neighbours[1] } else { neighbours[0] };
+                    if visited[neighbour] { continue; }
+
+                    // A consistent neighbour runs along the shared edge in the opposite direction
+                    let [d, e, f] = self.faces[neighbour].indices();
+                    if [(d, e), (e, f), (f, d)].contains(&(from, to)) {
+                        self.faces[neighbour].flip();
+                    }
+
+                    visited[neighbour] = true;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+    }
+
+    /// Faces using every undirected edge, with the smallest node index first
+    fn edge_faces(&self, faces: &[usize]) -> HashMap<(usize, usize), Vec<usize>> {
+        let mut edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+        for &index in faces {
+            let [a, b, c] = self.faces[index].indices();
+
+            for (from, to) in [(a, b), (b, c), (c, a)] {
+                edges.entry((from.min(to), from.max(to))).or_default().push(index);
+            }
+        }
+
+        edges
+    }
+
+    /// Whether the face repeats a node or has no area
+    fn is_degenerate(&self, face: &Face) -> bool {
+        let [a, b, c] = face.indices();
+        if a == b || b == c || c == a { return true; }
+
+        let origin: Pos4D = self.nodes[a].pos.into();
+        let u = self.nodes[b].pos.into() - origin;
+        let v = self.nodes[c].pos.into() - origin;
+
+        // Area of the parallelogram, from the wedge product so nearly parallel sides do not cancel out
+        // like they do in the Gram determinant. Rounding leaves collinear nodes about 1e-7 off their line.
+        let area = Bivector::wedge(u, v).len();
+
+        area <= u.len() * v.len() * 1e-5
+    }
+}
@@ -1,35 +1,76 @@
-use std::{sync::{Arc, Mutex}, thread, usize};
+use std::{collections::HashMap, sync::{Arc, Mutex}, thread};
 
-use crate::{pos::{Empty, Pos2D, Pos3D, Pos4D}, print_point, projection::{Project2D, Project3D, Projection}, render::{Color, Render, Screen}};
+use crate::{pos::{Empty, Pos2D, Pos3D, Pos4D}, projection::{Project2D, Project3D, Projection}, render::{Canvas, Color, Primitive, Render}};
 
 #[derive(Debug, Clone)]
 pub struct Object<T> {
     pub nodes: Vec<Node<T>>,
+    pub edges: Vec<Edge>,
     pub faces: Vec<Face>,
+    /// Tetrahedra filling the volume of the object, not drawn
+    pub cells: Vec<Cell>,
+    /// Unit normal of every node, empty if the object has none
+    pub normals: Vec<T>,
+    /// Which faces are skipped based on their winding, `Back` by default
+    pub cull: CullMode,
 }
 
 impl<T> Object<T> {
     pub fn new(nodes: Vec<Node<T>>, faces: Vec<Face>) -> Self {
         Self {
             nodes,
+            edges: Vec::new(),
             faces,
+            cells: Vec::new(),
+            normals: Vec::new(),
+            cull: CullMode::Back,
         }
     }
+
+    /// Indices of faces sharing an edge with an earlier face that runs in the same direction
+    ///
+    /// Faces of a closed surface with consistent winding traverse every shared edge in opposite directions,
+    /// so an empty list means culling treats all faces the same way.
+    pub fn inconsistent_winding(&self) -> Vec<usize> {
+        self.winding_conflicts(0..self.faces.len())
+    }
+
+    /// Like `inconsistent_winding`, but only looking at the given faces
+    pub(crate) fn winding_conflicts(&self, faces: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut inconsistent = Vec::new();
+
+        for index in faces {
+            let face = &self.faces[index];
+            let [a, b, c] = [face.node_a_index, face.node_b_index, face.node_c_index];
+
+            for edge in [(a, b), (b, c), (c, a)] {
+                if edges.insert(edge, index).is_some() {
+                    inconsistent.push(index);
+                    break;
+                }
+            }
+        }
+
+        inconsistent
+    }
 }
 
 impl From<Object<Pos3D>> for Object<Pos4D> {
     fn from(val: Object<Pos3D>) -> Self {
         let nodes = val.nodes.iter().map(|&node| node.into()).collect();
+        let normals = val.normals.iter().map(|&normal| normal.into()).collect();
 
-        Object { nodes, faces: val.faces }
+        Object { nodes, edges: val.edges, faces: val.faces, cells: val.cells, normals, cull: val.cull }
     }
 }
 
 impl From<Object<Pos4D>> for Object<Pos3D> {
     fn from(val: Object<Pos4D>) -> Self {
         let nodes = val.nodes.iter().map(|&node| node.into()).collect();
+        let normals = val.normals.iter().map(|&normal| normal.into()).collect();
 
-        Object { nodes, faces: val.faces }
+        Object { nodes, edges: val.edges, faces: val.faces, cells: val.cells, normals, cull: val.cull }
     }
 }
 
@@ -62,6 +103,43 @@ impl From<Node<Pos3D>> for Node<Pos4D> {
     }
 }
 
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Edge {
+    pub node_a_index: usize,
+    pub node_b_index: usize,
+    pub r: usize,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Cell {
+    pub node_a_index: usize,
+    pub node_b_index: usize,
+    pub node_c_index: usize,
+    pub node_d_index: usize,
+}
+
+/// Faces to skip based on the side facing the camera
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CullMode {
+    /// Draw every face, needed for open surfaces and surfaces in 4D which have no outside
+    None,
+    /// Skip faces whose nodes appear clockwise from the camera
+    Back,
+    /// Skip faces whose nodes appear counterclockwise from the camera, showing the inside of closed surfaces
+    Front,
+}
+
+impl CullMode {
+    /// Whether a face with the given cosine to the camera is drawn
+    pub fn keeps(&self, angle_to_camera: f32) -> bool {
+        match self {
+            CullMode::None => true,
+            CullMode::Back => angle_to_camera >= 0.0,
+            CullMode::Front => angle_to_camera <= 0.0,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct Face {
     pub node_a_index: usize,
@@ -72,9 +150,11 @@ pub struct Face {
 
 impl<T> Object<T> where T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Into<Pos3D> + std::hash::Hash + Copy + std::marker::Sync + std::marker::Send + 'static {
     /// Draw all edges, vertices and faces of the object
-    pub fn draw(
+    ///
+    /// Supersampled screens are not resolved, so several objects can be drawn before resolving the frame once.
+    pub fn draw<C: Canvas>(
         &self,
-        screen: Arc<Mutex<Screen>>,
+        screen: Arc<Mutex<C>>,
         projection: Projection,
     ) {        
         let screen_size = screen.lock().unwrap().size();
@@ -85,52 +165,63 @@ impl<T> Object<T> where T: Project2D<Output = (Pos2D, f32)> + Project3D<Output =
 
         for chunk in self.nodes.chunks(chunk_size) {
             let chunk = chunk.to_vec();
-            let cloned_screen = Arc::clone(&screen);
             let cloned_nodes = self.nodes.clone();
 
             let handle = thread::spawn(move || {
-                let mut local_changes: Vec<(Pos2D, usize, Color, f32)> = Vec::new();
+                let mut local_changes: Vec<Primitive> = Vec::new();
 
                 for node in chunk {
                     local_changes.append(&mut node.draw(&cloned_nodes, screen_size, projection));
                 }
 
-                {
-                    let mut screen = cloned_screen.lock().expect("Failed to lock the screen mutex");
-                    for (pos, r, color, depth) in local_changes {
-                        print_point(pos.x as usize, pos.y as usize, r, &mut *screen, color, depth);
-                    }
+                local_changes
+            });
+
+            handles.push(handle);
+        }
+
+        for chunk in self.edges.chunks(chunk_size) {
+            let chunk = chunk.to_vec();
+            let cloned_nodes = self.nodes.clone();
+
+            let handle = thread::spawn(move || {
+                let mut local_changes: Vec<Primitive> = Vec::new();
+
+                for edge in chunk {
+                    local_changes.append(&mut edge.draw(&cloned_nodes, screen_size, projection));
                 }
+
+                local_changes
             });
 
             handles.push(handle);
         }
 
+        let cull = self.cull;
+
         for chunk in self.faces.chunks(chunk_size) {
             let chunk = chunk.to_vec();
-            let cloned_screen = Arc::clone(&screen);
             let cloned_nodes = self.nodes.clone();
 
             let handle = thread::spawn(move || {
-                let mut local_changes: Vec<(Pos2D, usize, Color, f32)> = Vec::new();
+                let mut local_changes: Vec<Primitive> = Vec::new();
 
                 for face in chunk {
-                    local_changes.append(&mut face.draw(&cloned_nodes, screen_size, projection))
+                    local_changes.append(&mut face.draw_culled(&cloned_nodes, screen_size, projection, cull))
                 }
 
-                {
-                    let mut screen = cloned_screen.lock().expect("Failed to lock the screen mutex");
-                    for (pos, r, color, depth) in local_changes {
-                        print_point(pos.x as usize, pos.y as usize, r, &mut *screen, color, depth);
-                    }
-                }
+                local_changes
             });
 
             handles.push(handle);
         }
 
+        // Write the results in the order the threads were spawned so the output does not depend on scheduling
+        let mut screen = screen.lock().expect("Failed to lock the screen mutex");
         for handle in handles {
-            handle.join().unwrap();
+            for primitive in handle.join().unwrap() {
+                primitive.draw(&mut *screen);
+            }
         }
     }
 }
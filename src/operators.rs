@@ -0,0 +1,239 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    object::{Cell, CullMode, Edge, Face, Node, Object},
+    pos::{Pos3D, Pos4D},
+    render::Color,
+};
+
+/// Edges, triangles and tetrahedra of an object including the ones only implied by larger simplices,
+/// with the node indices of every simplex sorted
+struct Simplices {
+    /// Radius of every edge, 0 for edges that only appear in faces or cells
+    edges: BTreeMap<(usize, usize), usize>,
+    faces: BTreeSet<[usize; 3]>,
+    cells: BTreeSet<[usize; 4]>,
+}
+
+impl Simplices {
+    fn new<T>(object: &Object<T>) -> Self {
+        let mut cells = BTreeSet::new();
+        for cell in object.cells.iter() {
+            let mut key = [cell.node_a_index, cell.node_b_index, cell.node_c_index, cell.node_d_index];
+            key.sort();
+            cells.insert(key);
+        }
+
+        let mut faces = BTreeSet::new();
+        for face in object.faces.iter() {
+            let mut key = [face.node_a_index, face.node_b_index, face.node_c_index];
+            key.sort();
+            faces.insert(key);
+        }
+        for &[a, b, c, d] in cells.iter() {
+            faces.extend([[a, b, c], [a, b, d], [a, c, d], [b, c, d]]);
+        }
+
+        let mut edges = BTreeMap::new();
+        for &[a, b, c] in faces.iter() {
+            for edge in [(a, b), (a, c), (b, c)] {
+                edges.insert(edge, 0);
+            }
+        }
+        for edge in object.edges.iter() {
+            let (a, b) = (edge.node_a_index.min(edge.node_b_index), edge.node_a_index.max(edge.node_b_index));
+            let r = edges.entry((a, b)).or_insert(0);
+            *r = edge.r.max(*r);
+        }
+
+        Self { edges, faces, cells }
+    }
+}
+
+/// Collects the simplices of a new object without duplicates, together with every simplex on their boundary
+#[derive(Default)]
+struct Builder {
+    edges: BTreeMap<(usize, usize), usize>,
+    faces: BTreeSet<[usize; 3]>,
+    cells: BTreeSet<[usize; 4]>,
+}
+
+impl Builder {
+    fn edge(&mut self, a: usize, b: usize, r: usize) {
+        let r_old = self.edges.entry((a.min(b), a.max(b))).or_insert(0);
+        *r_old = r.max(*r_old);
+    }
+
+    fn face(&mut self, mut face: [usize; 3]) {
+        face.sort();
+
+        let [a, b, c] = face;
+        if self.faces.insert(face) {
+            for (p, q) in [(a, b), (a, c), (b, c)] {
+                self.edge(p, q, 0);
+            }
+        }
+    }
+
+    fn cell(&mut self, mut cell: [usize; 4]) {
+        cell.sort();
+
+        let [a, b, c, d] = cell;
+        if self.cells.insert(cell) {
+            for face in [[a, b, c], [a, b, d], [a, c, d], [b, c, d]] {
+                self.face(face);
+            }
+        }
+    }
+
+    fn build(self, nodes: Vec<Node<Pos4D>>) -> Object<Pos4D> {
+        Object {
+            nodes,
+            edges: self.edges.into_iter().map(|((a, b), r)| Edge { node_a_index: a, node_b_index: b, r }).collect(),
+            faces: self.faces.into_iter().map(|[a, b, c]| Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 }).collect(),
+            cells: self.cells.into_iter().map(|[a, b, c, d]| Cell { node_a_index: a, node_b_index: b, node_c_index: c, node_d_index: d }).collect(),
+            normals: Vec::new(),
+            // Faces of a 4D object have no outside
+            cull: CullMode::None,
+        }
+    }
+}
+
+impl Object<Pos4D> {
+    /// Cartesian product of two objects in the xy plane, the first one ends up in xy and the second in zw
+    ///
+    /// A polygon times a polygon gives a duoprism, a circle times a square a cubinder. Every face implied by
+    /// the cells of either object is multiplied as well, so objects with cells give triangles inside the
+    /// solid of the product too.
+    ///
+    /// # Panics
+    ///
+    /// Both objects have to lie in the plane z = 0, since there is no room left for their z.
+    pub fn product(a: &Object<Pos3D>, b: &Object<Pos3D>) -> Object<Pos4D> {
+        assert!(a.nodes.iter().chain(b.nodes.iter()).all(|node| node.pos.z == 0.0), "Object::product needs planar objects");
+
+        product(a, b, |a, b| Pos4D { x: a.x, y: a.y, z: b.x, w: b.y })
+    }
+
+    /// Extrude a 3D object along w into a prism of the given height, centered on w = 0
+    ///
+    /// The edges along w are as thick as the thickest edge of the object.
+    pub fn prism(object: &Object<Pos3D>, height: f32) -> Object<Pos4D> {
+        let segment = Object {
+            edges: vec![Edge { node_a_index: 0, node_b_index: 1, r: edge_r(object) }],
+            ..Object::new(vec![
+                Node { pos: Pos3D { x: -height / 2.0, y: 0.0, z: 0.0 }, color: Color::White, r: 0 },
+                Node { pos: Pos3D { x: height / 2.0, y: 0.0, z: 0.0 }, color: Color::White, r: 0 },
+            ], Vec::new())
+        };
+
+        product(object, &segment, |a, b| Pos4D { w: b.x, ..a.into() })
+    }
+
+    /// Connect every simplex of a 3D object at w = 0 to a single apex
+    ///
+    /// The edges to the apex are as thick as the thickest edge of the object, the same goes for `bipyramid`.
+    pub fn pyramid(object: &Object<Pos3D>, apex: Pos4D) -> Object<Pos4D> {
+        join(object, &[apex])
+    }
+
+    /// Connect every simplex of a 3D object at w = 0 to apexes at `height` above and below it along w
+    pub fn bipyramid(object: &Object<Pos3D>, height: f32) -> Object<Pos4D> {
+        let apex = Pos4D { x: 0.0, y: 0.0, z: 0.0, w: height };
+
+        join(object, &[apex, apex * -1.0])
+    }
+}
+
+/// Product of the simplicial complexes of two objects, splitting the prisms and squares into simplices
+///
+/// Prisms are split into a staircase along the sorted node indices, so neighbouring prisms agree on the
+/// diagonals of the squares they share.
+fn product<F>(a: &Object<Pos3D>, b: &Object<Pos3D>, combine: F) -> Object<Pos4D>
+where
+    F: Fn(Pos3D, Pos3D) -> Pos4D,
+{
+    let index = |i: usize, j: usize| i * b.nodes.len() + j;
+
+    let mut nodes = Vec::with_capacity(a.nodes.len() * b.nodes.len());
+    for node_a in a.nodes.iter() {
+        for node_b in b.nodes.iter() {
+            nodes.push(Node {
+                pos: combine(node_a.pos, node_b.pos),
+                color: node_a.color.mix(node_b.color, 0.5),
+                r: node_a.r.max(node_b.r),
+            });
+        }
+    }
+
+    let (sa, sb) = (Simplices::new(a), Simplices::new(b));
+    let mut builder = Builder::default();
+
+    // Simplices of one object at every node of the other
+    for j in 0..b.nodes.len() {
+        for (&(p, q), &r) in sa.edges.iter() { builder.edge(index(p, j), index(q, j), r); }
+        for &[p, q, s] in sa.faces.iter() { builder.face([index(p, j), index(q, j), index(s, j)]); }
+        for &[p, q, s, t] in sa.cells.iter() { builder.cell([index(p, j), index(q, j), index(s, j), index(t, j)]); }
+    }
+    for i in 0..a.nodes.len() {
+        for (&(p, q), &r) in sb.edges.iter() { builder.edge(index(i, p), index(i, q), r); }
+        for &[p, q, s] in sb.faces.iter() { builder.face([index(i, p), index(i, q), index(i, s)]); }
+        for &[p, q, s, t] in sb.cells.iter() { builder.cell([index(i, p), index(i, q), index(i, s), index(i, t)]); }
+    }
+
+    // Edge times edge is a square, split along the diagonal from the smallest to the largest corner
+    for &(i, j) in sa.edges.keys() {
+        for &(k, l) in sb.edges.keys() {
+            builder.face([index(i, k), index(j, k), index(j, l)]);
+            builder.face([index(i, k), index(i, l), index(j, l)]);
+        }
+    }
+
+    // Triangle times edge is a prism, split into three tetrahedra
+    for &[i, j, m] in sa.faces.iter() {
+        for &(k, l) in sb.edges.keys() {
+            builder.cell([index(i, k), index(j, k), index(m, k), index(m, l)]);
+            builder.cell([index(i, k), index(j, k), index(j, l), index(m, l)]);
+            builder.cell([index(i, k), index(i, l), index(j, l), index(m, l)]);
+        }
+    }
+    for &(i, j) in sa.edges.keys() {
+        for &[k, l, n] in sb.faces.iter() {
+            builder.cell([index(i, k), index(i, l), index(i, n), index(j, n)]);
+            builder.cell([index(i, k), index(i, l), index(j, l), index(j, n)]);
+            builder.cell([index(i, k), index(j, k), index(j, l), index(j, n)]);
+        }
+    }
+
+    builder.build(nodes)
+}
+
+/// Join every simplex of the object with every apex
+fn join(object: &Object<Pos3D>, apexes: &[Pos4D]) -> Object<Pos4D> {
+    let color = object.nodes.first().map_or(Color::White, |node| node.color);
+    let r = edge_r(object);
+
+    let mut nodes: Vec<Node<Pos4D>> = object.nodes.iter().map(|&node| node.into()).collect();
+    let simplices = Simplices::new(object);
+    let mut builder = Builder::default();
+
+    for (&(p, q), &r) in simplices.edges.iter() { builder.edge(p, q, r); }
+    for &face in simplices.faces.iter() { builder.face(face); }
+    for &cell in simplices.cells.iter() { builder.cell(cell); }
+
+    for &apex in apexes {
+        let top = nodes.len();
+        nodes.push(Node { pos: apex, color, r: 0 });
+
+        for node in 0..object.nodes.len() { builder.edge(node, top, r); }
+        for &(p, q) in simplices.edges.keys() { builder.face([p, q, top]); }
+        for &[p, q, s] in simplices.faces.iter() { builder.cell([p, q, s, top]); }
+    }
+
+    builder.build(nodes)
+}
+
+/// Radius of the thickest edge of an object, 0 without edges
+fn edge_r<T>(object: &Object<T>) -> usize {
+    object.edges.iter().map(|edge| edge.r).max().unwrap_or(0)
+}
@@ -0,0 +1,165 @@
+use crate::{
+    object::{Cell, CullMode, Face, Node, Object},
+    pos::{Len, Pos3D, Pos4D},
+    render::Color,
+};
+
+/// Sample a surface `f(u, v)` with u and v between 0 and 1 on a grid and triangulate it
+///
+/// Wrapped directions connect the last row of samples back to the first instead of sampling 1 again,
+/// nodes that end up on top of each other, like the poles of a sphere, are welded so the surface is closed.
+/// Faces are wound so their normal points along `df/du x df/dv`.
+pub fn parametric_surface<F>(u_res: usize, v_res: usize, wrap: (bool, bool), color: Color, f: F) -> Object<Pos3D>
+where
+    F: Fn(f32, f32) -> Pos3D,
+{
+    let mut object = grid_surface(u_res, v_res, wrap, color, f);
+    object.normals = vertex_normals(&object);
+
+    object
+}
+
+/// Sample a surface `f(u, v)` in 4D like `parametric_surface`
+///
+/// A surface in 4D has no single normal and no outside, so the faces are not culled and there are no normals.
+pub fn parametric_surface_4d<F>(u_res: usize, v_res: usize, wrap: (bool, bool), color: Color, f: F) -> Object<Pos4D>
+where
+    F: Fn(f32, f32) -> Pos4D,
+{
+    Object {
+        cull: CullMode::None,
+        ..grid_surface(u_res, v_res, wrap, color, f)
+    }
+}
+
+fn grid_surface<T, F>(u_res: usize, v_res: usize, wrap: (bool, bool), color: Color, f: F) -> Object<T>
+where
+    T: Into<Pos4D> + Copy,
+    F: Fn(f32, f32) -> T,
+{
+    // Wrapping needs at least three segments to not fold back onto itself
+    let u_res = u_res.max(if wrap.0 { 3 } else { 1 });
+    let v_res = v_res.max(if wrap.1 { 3 } else { 1 });
+
+    // Number of samples along every direction, wrapped directions do not repeat the first sample
+    let u_count = if wrap.0 { u_res } else { u_res + 1 };
+    let v_count = if wrap.1 { v_res } else { v_res + 1 };
+
+    let mut nodes = Vec::with_capacity(u_count * v_count);
+    for j in 0..v_count {
+        for i in 0..u_count {
+            let pos = f(i as f32 / u_res as f32, j as f32 / v_res as f32);
+
+            nodes.push(Node { pos, color, r: 0 });
+        }
+    }
+
+    let index = |i: usize, j: usize| (i % u_count) + (j % v_count) * u_count;
+
+    let mut faces = Vec::with_capacity(u_res * v_res * 2);
+    for j in 0..v_res {
+        for i in 0..u_res {
+            let [a, b, c, d] = [index(i, j), index(i + 1, j), index(i + 1, j + 1), index(i, j + 1)];
+
+            faces.push(Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 });
+            faces.push(Face { node_a_index: a, node_b_index: c, node_c_index: d, r: 1 });
+        }
+    }
+
+    let mut object = Object::new(nodes, faces);
+    weld_relative(&mut object);
+
+    object
+}
+
+/// Piece of a hypersurface, `f(u, v, s)` sampled with u, v and s between 0 and 1
+pub struct Patch<'a> {
+    /// Number of segments along u, v and s
+    pub res: [usize; 3],
+    /// Whether u, v and s connect back to the start
+    pub wrap: [bool; 3],
+    pub f: &'a dyn Fn(f32, f32, f32) -> Pos4D,
+}
+
+/// Sample the patches on grids and fill them with tetrahedra, patches meeting at their borders are joined
+///
+/// Every grid cube is split into 6 tetrahedra along its main diagonal, which line up between neighbouring cubes.
+pub fn parametric_volume(patches: &[Patch], color: Color) -> Object<Pos4D> {
+    let mut nodes = Vec::new();
+    let mut cells = Vec::new();
+
+    for patch in patches {
+        let res = [0, 1, 2].map(|i| patch.res[i].max(if patch.wrap[i] { 3 } else { 1 }));
+        let count = [0, 1, 2].map(|i| if patch.wrap[i] { res[i] } else { res[i] + 1 });
+
+        let offset = nodes.len();
+        let index = |p: [usize; 3]| offset + (p[0] % count[0]) + (p[1] % count[1]) * count[0] + (p[2] % count[2]) * count[0] * count[1];
+
+        for k in 0..count[2] {
+            for j in 0..count[1] {
+                for i in 0..count[0] {
+                    let pos = (patch.f)(i as f32 / res[0] as f32, j as f32 / res[1] as f32, k as f32 / res[2] as f32);
+
+                    nodes.push(Node { pos, color, r: 0 });
+                }
+            }
+        }
+
+        // Walk from the first to the opposite corner of the cube, one axis at a time in every possible order
+        const ORDERS: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+        for k in 0..res[2] {
+            for j in 0..res[1] {
+                for i in 0..res[0] {
+                    for order in ORDERS {
+                        let mut corner = [i, j, k];
+                        let mut tetrahedron = [index(corner); 4];
+
+                        for (step, axis) in order.into_iter().enumerate() {
+                            corner[axis] += 1;
+                            tetrahedron[step + 1] = index(corner);
+                        }
+
+                        let [a, b, c, d] = tetrahedron;
+                        cells.push(Cell { node_a_index: a, node_b_index: b, node_c_index: c, node_d_index: d });
+                    }
+                }
+            }
+        }
+    }
+
+    let mut object = Object { cells, ..Object::new(nodes, Vec::new()) };
+    weld_relative(&mut object);
+
+    Object::from_cells(object.nodes, object.cells, 5)
+}
+
+/// Merge nodes the parametrization maps to the same position, relative to the size of the object
+fn weld_relative<T>(object: &mut Object<T>) where T: Into<Pos4D> + Copy {
+    let size = object.nodes.iter().fold(0.0_f32, |size, node| size.max(node.pos.into().len()));
+
+    object.weld(size * 1e-5);
+}
+
+/// Normals of every node, the average of the normals of the faces around it weighted by their area
+pub fn vertex_normals(object: &Object<Pos3D>) -> Vec<Pos3D> {
+    let mut normals = vec![Pos3D { x: 0.0, y: 0.0, z: 0.0 }; object.nodes.len()];
+
+    for face in object.faces.iter() {
+        let [a, b, c] = [face.node_a_index, face.node_b_index, face.node_c_index];
+        let origin = object.nodes[a].pos;
+
+        // The length of the cross product is twice the area
+        let normal = (object.nodes[b].pos - origin) ^ (object.nodes[c].pos - origin);
+
+        for index in [a, b, c] {
+            normals[index] += normal;
+        }
+    }
+
+    normals.into_iter().map(|normal| {
+        let len = normal.len();
+
+        if len > 0.0 { normal / len } else { normal }
+    }).collect()
+}
@@ -673,10 +673,10 @@ impl ops::Sub for Pos4D {
     type Output = Pos4D;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let x: f32 = rhs.x - self.x;
-        let y: f32 = rhs.y - self.y;
-        let z: f32 = rhs.z - self.z;
-        let w: f32 = rhs.w - self.w;
+        let x: f32 = self.x - rhs.x;
+        let y: f32 = self.y - rhs.y;
+        let z: f32 = self.z - rhs.z;
+        let w: f32 = self.w - rhs.w;
 
         Self::Output { x, y, z, w }
     }
@@ -7,12 +7,31 @@ use crate::pos::*;
 pub struct Projection {
     pub r#type: ProjectionType,
     pub scale: f32,
+    /// View distance mapped to depth 0, measured from the origin along the viewing direction
+    pub near: f32,
+    /// View distance mapped to depth 1, swap with `near` for reversed depth
+    pub far: f32,
 }
 
 impl Projection {
+    /// Projection with the near and far planes around everything that fits on the screen at this scale
     pub fn new(r#type: ProjectionType, scale: f32) -> Self {
+        use self::ProjectionType::*;
+        let reach = match r#type {
+            // From the eye to where objects vanish
+            Perspective => 3.0 * scale,
+            // Half of a 2000 pixel wide screen
+            Stereographic => 10.0 / scale,
+            Collapse => 1000.0 / scale,
+        };
+
+        Self::with_depth_range(r#type, scale, -reach, reach)
+    }
+
+    /// Projection with the view distances mapped to depth 0 and 1
+    pub fn with_depth_range(r#type: ProjectionType, scale: f32, near: f32, far: f32) -> Self {
         Self {
-            r#type, scale
+            r#type, scale, near, far,
         }
     }
 }
@@ -38,29 +57,60 @@ pub trait Project3D {
 
 impl Projection {
     /// Get the position of the camera based on the type of projection
+    ///
+    /// Only the perspective projection has an eye at a finite distance, for the other projections
+    /// this is the unit vector pointing towards the camera.
     pub fn get_camera_pos(&self) -> Pos3D {
         use self::ProjectionType::*;
         match self.r#type {
+            // Objects shrink linearly with x and would vanish at 3 times the scale, which matches an eye this far behind the origin
             Perspective => Pos3D {
-                x: -1.0,
+                x: -3.0 * self.scale,
                 y: 0.0,
                 z: 0.0,
             },
+            // Opposite of the direction into the screen of the isometric screen matrix
             Stereographic => Pos3D {
                 x: 1.0,
-                y: 0.0,
-                z: 0.0,
-            },
+                y: -1.0,
+                z: 1.0,
+            } * (1.0 / 3.0_f32.sqrt()),
             Collapse => Pos3D {
                 x: 0.0,
                 y: 0.0,
-                z: 1.0,
+                z: -1.0,
             },
         }
     }
 
+    /// Direction from a projected position towards the camera
+    pub fn to_camera(&self, pos: Pos3D) -> Pos3D {
+        match self.r#type {
+            ProjectionType::Perspective => self.get_camera_pos() + pos * -1.0,
+            _ => self.get_camera_pos(),
+        }
+    }
+
+    /// Distance of a projected position from the origin along the viewing direction, larger is further from the camera
+    ///
+    /// The direction to the right on the screen, the one down the screen and this one are right-handed for every projection.
+    pub fn view_distance(&self, pos: Pos3D) -> f32 {
+        use self::ProjectionType::*;
+        match self.r#type {
+            Perspective => pos.x,
+            // Direction into the screen of the isometric screen matrix
+            Stereographic => (-pos.x + pos.y - pos.z) / 3.0_f32.sqrt(),
+            Collapse => pos.z,
+        }
+    }
+
+    /// Depth of a projected position, 0 on the near plane and 1 on the far plane
+    pub fn depth(&self, pos: Pos3D) -> f32 {
+        (self.view_distance(pos) - self.near) / (self.far - self.near)
+    }
+
     pub fn project<T, U>(&self, pos: T, size: (usize, usize)) -> (U, f32) where T: Project2D<Output = (U, f32)> {
-        pos.project_2d(&self, size)
+        pos.project_2d(self, size)
     }
 }
 
@@ -126,24 +176,17 @@ impl Project2D for Pos3D {
                     y: (screen_size.1 as f32 / 2.0 + zratio * bound * (self.y / projection.scale)).floor(),
                 };
 
-                // Calculate the screen depth of the pixel
-                let depth = {
-                    10.0 / 2.0 - zratio * bound * (self.z / projection.scale)
-                };
-
-                (screen_pos, depth)
+                (screen_pos, projection.depth(*self))
             }
             Stereographic => {
                 let screen_pos = (SCREEN_MATRIX_3D * *self).to_screen_coords(projection.scale * 100.0, screen_size);
-                let depth = 0.0;
 
-                (screen_pos, depth)
+                (screen_pos, projection.depth(*self))
             },
             Collapse => {
                 let screen_pos = Pos2D { x: self.x, y: self.y }.to_screen_coords(projection.scale, screen_size);
-                let depth = self.z / 10.0;
 
-                (screen_pos, depth)
+                (screen_pos, projection.depth(*self))
             },
         }
     }
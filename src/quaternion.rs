@@ -0,0 +1,299 @@
+use std::ops;
+
+use crate::{matrix::{Matrix3x3, Matrix4x4}, pos::{Len, Pos3D, Pos4D}};
+
+/// Quaternion `w + xi + yj + zk`, used as a drift free representation of 3D rotations
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    pub fn new(w: f32, x: f32, y: f32, z: f32) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Self { w: 1.0, x: 0.0, y: 0.0, z: 0.0 }
+    }
+
+    /// Create a unit quaternion rotating by `angle` radians around `axis`
+    pub fn from_axis_angle(axis: Pos3D, angle: f32) -> Self {
+        let axis = axis / axis.len();
+        let (sin, cos) = (angle / 2.0).sin_cos();
+
+        Self { w: cos, x: axis.x * sin, y: axis.y * sin, z: axis.z * sin }
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self { w: self.w, x: -self.x, y: -self.y, z: -self.z }
+    }
+
+    /// Scale the quaternion back to unit length to remove accumulated rounding errors
+    pub fn normalize(&self) -> Self {
+        let len = self.len();
+
+        if len == 0.0 {
+            return Self::identity();
+        }
+
+        *self * (1.0 / len)
+    }
+
+    /// Rotate a point using `q * p * q^-1`
+    pub fn rotate(&self, pos: Pos3D) -> Pos3D {
+        let p = Quaternion { w: 0.0, x: pos.x, y: pos.y, z: pos.z };
+        let r = *self * p * self.conjugate();
+
+        Pos3D { x: r.x, y: r.y, z: r.z }
+    }
+
+    /// Spherical linear interpolation between two rotations, taking the shortest path
+    pub fn slerp(&self, other: Quaternion, t: f32) -> Self {
+        // q and -q describe the same rotation, pick the one closest to self
+        let other = if (*self >> other) < 0.0 { other * -1.0 } else { other };
+
+        self.slerp_unchecked(other, t)
+    }
+
+    /// Spherical linear interpolation without flipping the sign of `other`
+    pub(crate) fn slerp_unchecked(&self, other: Quaternion, t: f32) -> Self {
+        let cos = (*self >> other).clamp(-1.0, 1.0);
+
+        // Fall back to a normalized lerp when the quaternions are (nearly) parallel
+        if cos > 0.9995 {
+            return (*self * (1.0 - t) + other * t).normalize();
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+
+        let a = ((1.0 - t) * angle).sin() / sin;
+        let b = (t * angle).sin() / sin;
+
+        *self * a + other * b
+    }
+}
+
+impl Len for Quaternion {
+    fn len(&self) -> f32 {
+        (self.w.powi(2) + self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.w == 0.0 && self.x == 0.0 && self.y == 0.0 && self.z == 0.0
+    }
+}
+
+impl ops::Add for Quaternion {
+    type Output = Quaternion;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::Output { w: self.w + rhs.w, x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl ops::Mul<f32> for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self::Output { w: self.w * rhs, x: self.x * rhs, y: self.y * rhs, z: self.z * rhs }
+    }
+}
+
+// Hamilton product, rhs is applied first when used as a rotation
+impl ops::Mul for Quaternion {
+    type Output = Quaternion;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let w = self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z;
+        let x = self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y;
+        let y = self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x;
+        let z = self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w;
+
+        Self::Output { w, x, y, z }
+    }
+}
+
+// Use >> operator as a dot product operator
+impl ops::Shr for Quaternion {
+    type Output = f32;
+
+    fn shr(self, rhs: Self) -> Self::Output {
+        self.w * rhs.w + self.x * rhs.x + self.y * rhs.y + self.z * rhs.z
+    }
+}
+
+// A point in 4D space is stored as the quaternion `w + xi + yj + zk`
+impl From<Pos4D> for Quaternion {
+    fn from(val: Pos4D) -> Self {
+        Quaternion { w: val.w, x: val.x, y: val.y, z: val.z }
+    }
+}
+
+impl From<Quaternion> for Pos4D {
+    fn from(val: Quaternion) -> Self {
+        Pos4D { x: val.x, y: val.y, z: val.z, w: val.w }
+    }
+}
+
+impl From<Quaternion> for Matrix3x3 {
+    fn from(val: Quaternion) -> Self {
+        let Quaternion { w, x, y, z } = val.normalize();
+
+        Matrix3x3::new([
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - z * w), 2.0 * (x * z + y * w)],
+            [2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - x * w)],
+            [2.0 * (x * z - y * w), 2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y)],
+        ])
+    }
+}
+
+impl From<Matrix3x3> for Quaternion {
+    fn from(val: Matrix3x3) -> Self {
+        let m = val;
+        let trace = m.x.x + m.y.y + m.z.z;
+
+        // Divide by the largest of the four candidates to stay numerically stable
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion { w: 0.25 * s, x: (m.z.y - m.y.z) / s, y: (m.x.z - m.z.x) / s, z: (m.y.x - m.x.y) / s }
+        } else if m.x.x > m.y.y && m.x.x > m.z.z {
+            let s = (1.0 + m.x.x - m.y.y - m.z.z).sqrt() * 2.0;
+            Quaternion { w: (m.z.y - m.y.z) / s, x: 0.25 * s, y: (m.x.y + m.y.x) / s, z: (m.x.z + m.z.x) / s }
+        } else if m.y.y > m.z.z {
+            let s = (1.0 + m.y.y - m.x.x - m.z.z).sqrt() * 2.0;
+            Quaternion { w: (m.x.z - m.z.x) / s, x: (m.x.y + m.y.x) / s, y: 0.25 * s, z: (m.y.z + m.z.y) / s }
+        } else {
+            let s = (1.0 + m.z.z - m.x.x - m.y.y).sqrt() * 2.0;
+            Quaternion { w: (m.y.x - m.x.y) / s, x: (m.x.z + m.z.x) / s, y: (m.y.z + m.z.y) / s, z: 0.25 * s }
+        };
+
+        q.normalize()
+    }
+}
+
+/// Pair of unit quaternions describing the 4D rotation `p -> left * p * right`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DoubleQuaternion {
+    pub left: Quaternion,
+    pub right: Quaternion,
+}
+
+impl DoubleQuaternion {
+    pub fn new(left: Quaternion, right: Quaternion) -> Self {
+        Self { left, right }
+    }
+
+    pub fn identity() -> Self {
+        Self { left: Quaternion::identity(), right: Quaternion::identity() }
+    }
+
+    /// Embed a 3D rotation, leaving the w axis untouched
+    pub fn from_3d(rotation: Quaternion) -> Self {
+        let rotation = rotation.normalize();
+
+        Self { left: rotation, right: rotation.conjugate() }
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self { left: self.left.conjugate(), right: self.right.conjugate() }
+    }
+
+    /// Scale both quaternions back to unit length to remove accumulated rounding errors
+    pub fn normalize(&self) -> Self {
+        Self { left: self.left.normalize(), right: self.right.normalize() }
+    }
+
+    pub fn rotate(&self, pos: Pos4D) -> Pos4D {
+        (self.left * Quaternion::from(pos) * self.right).into()
+    }
+
+    /// Spherical linear interpolation between two rotations, taking the shortest path
+    pub fn slerp(&self, other: DoubleQuaternion, t: f32) -> Self {
+        // (l, r) and (-l, -r) describe the same rotation, but flipping only one of them does not
+        let other = if (self.left >> other.left) + (self.right >> other.right) < 0.0 {
+            DoubleQuaternion { left: other.left * -1.0, right: other.right * -1.0 }
+        } else {
+            other
+        };
+
+        Self {
+            left: self.left.slerp_unchecked(other.left, t),
+            right: self.right.slerp_unchecked(other.right, t),
+        }
+    }
+}
+
+// Composition, rhs is applied first, the same as for matrices
+impl ops::Mul for DoubleQuaternion {
+    type Output = DoubleQuaternion;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::Output { left: self.left * rhs.left, right: rhs.right * self.right }
+    }
+}
+
+impl From<DoubleQuaternion> for Matrix4x4 {
+    fn from(val: DoubleQuaternion) -> Self {
+        let val = val.normalize();
+
+        // The columns of the matrix are the images of the basis vectors
+        let x = val.rotate(Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 });
+        let y = val.rotate(Pos4D { x: 0.0, y: 1.0, z: 0.0, w: 0.0 });
+        let z = val.rotate(Pos4D { x: 0.0, y: 0.0, z: 1.0, w: 0.0 });
+        let w = val.rotate(Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 1.0 });
+
+        Matrix4x4::new([
+            [x.x, y.x, z.x, w.x],
+            [x.y, y.y, z.y, w.y],
+            [x.z, y.z, z.z, w.z],
+            [x.w, y.w, z.w, w.w],
+        ])
+    }
+}
+
+impl From<Matrix4x4> for DoubleQuaternion {
+    fn from(val: Matrix4x4) -> Self {
+        let rows = [val.x, val.y, val.z, val.w];
+        let basis = [
+            Quaternion::new(0.0, 1.0, 0.0, 0.0),
+            Quaternion::new(0.0, 0.0, 1.0, 0.0),
+            Quaternion::new(0.0, 0.0, 0.0, 1.0),
+            Quaternion::new(1.0, 0.0, 0.0, 0.0),
+        ];
+
+        // The maps p -> e_a * p * e_b are orthogonal signed permutations, so projecting the
+        // matrix onto them gives the associate matrix A[a][b] = l_a * r_b (van Elfrinkhof)
+        let mut associate = [[0.0_f32; 4]; 4];
+        for (a, &e_a) in basis.iter().enumerate() {
+            for (b, &e_b) in basis.iter().enumerate() {
+                associate[a][b] = basis.iter().enumerate().map(|(column, &e)| {
+                    let image = Pos4D::from(e_a * e * e_b);
+
+                    rows.iter().zip([image.x, image.y, image.z, image.w]).map(|(row, v)| {
+                        [row.x, row.y, row.z, row.w][column] * v
+                    }).sum::<f32>()
+                }).sum::<f32>() / 4.0;
+            }
+        }
+
+        // A is (close to) rank one, so its largest row is the best estimate of r
+        let largest = (0..4).max_by(|&a, &b| {
+            let len_a: f32 = associate[a].iter().map(|v| v * v).sum();
+            let len_b: f32 = associate[b].iter().map(|v| v * v).sum();
+            len_a.total_cmp(&len_b)
+        }).unwrap_or(0);
+
+        let to_quaternion = |v: [f32; 4]| Quaternion::new(v[3], v[0], v[1], v[2]);
+
+        let right = to_quaternion(associate[largest]).normalize();
+        let r = [right.x, right.y, right.z, right.w];
+        let left = to_quaternion([0, 1, 2, 3].map(|a| (0..4).map(|b| associate[a][b] * r[b]).sum())).normalize();
+
+        Self { left, right }
+    }
+}
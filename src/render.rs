@@ -1,6 +1,5 @@
-use std::usize;
-
-use crate::object::{Node, Face};
+use crate::object::{CullMode, Edge, Node, Face};
+use crate::print_point;
 use crate::pos::{Len, Pos2D, Pos3D};
 use crate::projection::{Projection, Project2D, Project3D};
 
@@ -16,7 +15,10 @@ pub enum Color {
     Black,
     RGBA(u8, u8, u8, u8),
     RGB(u8, u8, u8),
+    /// Hue in degrees, saturation and value from 0 to 255
     HSV(u16, u8, u8),
+    /// Hue in degrees, saturation and lightness from 0 to 255
+    HSL(u16, u8, u8),
 }
 
 impl Color {
@@ -33,55 +35,196 @@ impl Color {
             Color::RGBA(r, g, b, a) => [*r, *g, *b, *a],
             Color::RGB(r, g, b) => [*r, *g, *b, 0xff],
             Color::HSV(h, s, v) => {
-                let region = 3.0 * (*h / 180) as f32;
-                let c = *v as f32 * *s as f32;
-                let x = c * (1.0 - (region % 2.0 - 1.0).abs());
-        
-                let (r, g, b) = {
-                    if (0.0..1.0).contains(&region) {
-                        (c, x, 0.0)
-                    } else if (1.0..2.0).contains(&region) {
-                        (x, c, 0.0)
-                    } else if (2.0..3.0).contains(&region) {
-                        (0.0, c, x)
-                    } else if (3.0..4.0).contains(&region) {
-                        (0.0, x, c)
-                    } else if (4.0..5.0).contains(&region) {
-                        (x, 0.0, c)
-                    } else {
-                        (c, 0.0, x)
-                    }
-                };
-            
-                [
-                    ((r + (*v as f32 - c)) * 255.0).clamp(0.0, 255.0) as u8, 
-                    ((g + (*v as f32 - c)) * 255.0).clamp(0.0, 255.0) as u8, 
-                    ((b + (*v as f32 - c)) * 255.0).clamp(0.0, 255.0) as u8, 
-                    0xff,
-                ]
-            }
+                let s = *s as f32 / 255.0;
+                let v = *v as f32 / 255.0;
+
+                // Chroma and the value of the smallest component
+                let c = v * s;
+                hue_to_rgba(*h, c, v - c)
+            },
+            Color::HSL(h, s, l) => {
+                let s = *s as f32 / 255.0;
+                let l = *l as f32 / 255.0;
+
+                let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+                hue_to_rgba(*h, c, l - c / 2.0)
+            },
+        }
+    }
+
+    /// Components in linear light between 0 and 1, alpha is left as is
+    pub fn to_linear(&self) -> [f32; 4] {
+        let [r, g, b, a] = self.get_rgba();
+
+        [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a as f32 / 255.0]
+    }
+
+    /// Create a color from components in linear light between 0 and 1
+    pub fn from_linear(rgba: [f32; 4]) -> Color {
+        Color::RGBA(
+            linear_to_srgb(rgba[0]),
+            linear_to_srgb(rgba[1]),
+            linear_to_srgb(rgba[2]),
+            (rgba[3] * 255.0).round().clamp(0.0, 255.0) as u8,
+        )
+    }
+
+    /// Oklab coordinates `[L, a, b, alpha]`, a perceptually uniform color space
+    pub fn to_oklab(&self) -> [f32; 4] {
+        let [r, g, b, alpha] = self.to_linear();
+
+        let l = (0.41222146 * r + 0.53633255 * g + 0.051445995 * b).cbrt();
+        let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+        let s = (0.08830246 * r + 0.28171885 * g + 0.6299787 * b).cbrt();
+
+        [
+            0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+            alpha,
+        ]
+    }
+
+    /// Create a color from Oklab coordinates `[L, a, b, alpha]`
+    pub fn from_oklab(lab: [f32; 4]) -> Color {
+        let [l, a, b, alpha] = lab;
+
+        let l_ = (l + 0.39633778 * a + 0.21580376 * b).powi(3);
+        let m_ = (l - 0.105561346 * a - 0.06385417 * b).powi(3);
+        let s_ = (l - 0.08948418 * a - 1.2914855 * b).powi(3);
+
+        Color::from_linear([
+            4.0767417 * l_ - 3.3077116 * m_ + 0.23096994 * s_,
+            -1.268438 * l_ + 2.6097574 * m_ - 0.34131938 * s_,
+            -0.0041960863 * l_ - 0.7034186 * m_ + 1.7076147 * s_,
+            alpha,
+        ])
+    }
+
+    /// Interpolate between two colors in Oklab, so the steps look evenly spaced
+    pub fn mix(&self, other: Color, t: f32) -> Color {
+        let a = self.to_oklab();
+        let b = other.to_oklab();
+
+        Color::from_oklab([0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t))
+    }
+}
+
+/// Convert a hue with chroma `c` and smallest component `m` to rgba
+fn hue_to_rgba(h: u16, c: f32, m: f32) -> [u8; 4] {
+    let region = (h % 360) as f32 / 60.0;
+    let x = c * (1.0 - (region % 2.0 - 1.0).abs());
+
+    let (r, g, b) = {
+        if (0.0..1.0).contains(&region) {
+            (c, x, 0.0)
+        } else if (1.0..2.0).contains(&region) {
+            (x, c, 0.0)
+        } else if (2.0..3.0).contains(&region) {
+            (0.0, c, x)
+        } else if (3.0..4.0).contains(&region) {
+            (0.0, x, c)
+        } else if (4.0..5.0).contains(&region) {
+            (x, 0.0, c)
+        } else {
+            (c, 0.0, x)
+        }
+    };
+
+    [
+        ((r + m) * 255.0).round().clamp(0.0, 255.0) as u8,
+        ((g + m) * 255.0).round().clamp(0.0, 255.0) as u8,
+        ((b + m) * 255.0).round().clamp(0.0, 255.0) as u8,
+        0xff,
+    ]
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+
+    (c * 255.0).round() as u8
+}
+
+/// Filter used to combine the samples of a supersampled screen into pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    /// Average of the samples inside the pixel
+    Box,
+    /// Weighted average of the samples inside the pixel and its neighbours, smoother but slightly blurrier
+    Tent,
+}
+
+/// Test deciding if a new sample replaces the sample already on the screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthCompare {
+    /// Keep the smallest depth, the nearest sample with the default projection planes
+    Less,
+    /// Keep the largest depth, for reversed depth where the near plane is at 1
+    Greater,
+    /// Always replace, the last sample drawn ends up on top
+    Always,
+}
+
+impl DepthCompare {
+    /// Whether a new depth passes the test against the old depth
+    pub fn test(&self, new_depth: f32, old_depth: f32) -> bool {
+        match self {
+            DepthCompare::Less => new_depth < old_depth,
+            DepthCompare::Greater => new_depth > old_depth,
+            DepthCompare::Always => true,
         }
     }
 }
 
 pub struct Screen {
+    /// Test deciding which sample is kept, `Less` by default
+    pub depth_compare: DepthCompare,
+    /// Whether samples that pass the depth test write their depth, disable to draw overlays without occluding anything
+    pub depth_write: bool,
     color: Box<[[u8; 4]]>,
     depth: Box<[Option<f32>]>,
+    /// Width of the color and depth buffers in samples
     width: usize,
+    /// Number of samples along each axis of a pixel
+    samples: usize,
+    filter: Filter,
+    /// Colors of the samples combined into one color per pixel, only used when supersampling
+    resolved: Box<[[u8; 4]]>,
 }
 
 impl Screen {
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_supersampling(width, height, 1, Filter::Box)
+    }
+
+    /// Create a screen rendering `samples` x `samples` samples for every pixel to reduce aliasing
+    pub fn with_supersampling(width: usize, height: usize, samples: usize, filter: Filter) -> Self {
+        let samples = samples.max(1);
+        let resolved_len = if samples > 1 { width * height } else { 0 };
+
         Self {
-            color: vec![[0x00; 4]; width * height].into_boxed_slice(),
-            depth: vec![None; width * height].into_boxed_slice(),
-            width,
+            depth_compare: DepthCompare::Less,
+            depth_write: true,
+            color: vec![[0x00; 4]; width * height * samples * samples].into_boxed_slice(),
+            depth: vec![None; width * height * samples * samples].into_boxed_slice(),
+            width: width * samples,
+            samples,
+            filter,
+            resolved: vec![[0x00; 4]; resolved_len].into_boxed_slice(),
         }
     }
 
     pub fn clear(&mut self) {
         self.color = vec![[0x00; 4]; self.color.len()].into_boxed_slice();
         self.depth = vec![None; self.depth.len()].into_boxed_slice();
+        self.resolved = vec![[0x00; 4]; self.resolved.len()].into_boxed_slice();
     }
 
     #[allow(dead_code)]
@@ -123,42 +266,156 @@ impl Screen {
         }
     }
 
+    /// Write a color to a sample if it passes the depth test, samples are pixels unless the screen is supersampled
     pub fn write(&mut self, x: usize, y: usize, new_color: Color, new_depth: f32) -> Result<(), &'static str> {
         // Get the color and depth from the screen
         let old_depth = self.get_depth(x, y)?;
 
         // Test if the new pixel is in front of the old pixel
         if let Some(old_depth) = old_depth {
-            if old_depth < new_depth {
-                self.put_color(x, y, new_color.get_rgba())?;
-                self.put_depth(x, y, new_depth)?;
+            if !self.depth_compare.test(new_depth, old_depth) {
+                return Ok(());
             }
-        } else {
-            self.put_color(x, y, new_color.get_rgba())?;
+        }
+
+        self.put_color(x, y, new_color.get_rgba())?;
+
+        if self.depth_write {
             self.put_depth(x, y, new_depth)?;
         }
 
         Ok(())
     }
 
+    /// Size of the output image in pixels
     pub fn size(&self) -> (usize, usize) {
+        (self.width / self.samples, self.color.len() / self.width / self.samples)
+    }
+
+    /// Size of the color and depth buffers in samples
+    pub fn sample_size(&self) -> (usize, usize) {
         (self.width, self.color.len() / self.width)
     }
 
+    /// Number of samples along each axis of a pixel
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
     fn coord_to_index(&self, x: usize, y: usize) -> Result<usize, &'static str> {
-        if x > self.width || y > 1000000 {
-            return Err("Trying to draw pixel outside screen buffer");
-        }
-        if self.color.len() < x * y || self.depth.len() < x * y {
+        if x >= self.width || y >= self.color.len() / self.width {
             return Err("Trying to draw pixel outside screen buffer");
         }
 
         Ok(x + self.width * y)
     }
 
+    /// Combine the samples into pixels, averaging in linear light so edges keep their brightness
+    ///
+    /// Does nothing if the screen is not supersampled.
+    pub fn resolve(&mut self) {
+        if self.samples == 1 { return; }
+
+        let samples = self.samples as isize;
+        let (width, height) = self.size();
+        let (sample_width, sample_height) = self.sample_size();
+
+        // Weights of the samples along one axis, relative to the first sample of the pixel
+        let weights: Vec<(isize, f32)> = match self.filter {
+            Filter::Box => (0..samples).map(|k| (k, 1.0)).collect(),
+            Filter::Tent => (-samples..2 * samples).filter_map(|k| {
+                let distance = ((k as f32 + 0.5) - samples as f32 / 2.0).abs() / samples as f32;
+                if distance < 1.0 { Some((k, 1.0 - distance)) } else { None }
+            }).collect(),
+        };
+
+        let to_linear: Vec<f32> = (0..=255).map(srgb_to_linear).collect();
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut total_weight = 0.0;
+                let mut alpha = 0.0;
+                let mut rgb = [0.0; 3];
+
+                for &(dy, wy) in weights.iter() {
+                    let sy = (y as isize * samples + dy).clamp(0, sample_height as isize - 1) as usize;
+
+                    for &(dx, wx) in weights.iter() {
+                        let sx = (x as isize * samples + dx).clamp(0, sample_width as isize - 1) as usize;
+
+                        let [r, g, b, a] = self.color[sx + sy * sample_width];
+                        let weight = wx * wy;
+                        let coverage = weight * a as f32 / 255.0;
+
+                        total_weight += weight;
+                        alpha += coverage;
+                        rgb[0] += to_linear[r as usize] * coverage;
+                        rgb[1] += to_linear[g as usize] * coverage;
+                        rgb[2] += to_linear[b as usize] * coverage;
+                    }
+                }
+
+                self.resolved[x + y * width] = if alpha > 0.0 {
+                    Color::from_linear([rgb[0] / alpha, rgb[1] / alpha, rgb[2] / alpha, alpha / total_weight]).get_rgba()
+                } else {
+                    [0x00; 4]
+                };
+            }
+        }
+    }
+
+    /// Depth of every sample, None where nothing was drawn
+    pub fn get_depth_slice(&self) -> &[Option<f32>] {
+        &self.depth
+    }
+
+    /// Depth of a pixel, taken from the sample closest to its center since depths can not be averaged across edges
+    pub fn pixel_depth(&self, x: usize, y: usize) -> Option<f32> {
+        let half = self.samples / 2;
+
+        self.get_depth(x * self.samples + half, y * self.samples + half).ok().flatten()
+    }
+
+    /// Smallest and largest depth on the screen, None if nothing was drawn
+    pub fn depth_range(&self) -> Option<(f32, f32)> {
+        self.depth.iter().flatten().fold(None, |range, &d| match range {
+            Some((min, max)) => Some((d.min(min), d.max(max))),
+            None => Some((d, d)),
+        })
+    }
+
+    /// Depth of all pixels as rgba bytes, scaled so the nearest pixel is white and the furthest black
+    ///
+    /// Pixels without depth are transparent.
+    pub fn depth_to_rgba(&self) -> Vec<u8> {
+        let (width, height) = self.size();
+        let (min, max) = self.depth_range().unwrap_or((0.0, 0.0));
+
+        let mut rgba = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            for x in 0..width {
+                match self.pixel_depth(x, y) {
+                    Some(depth) => {
+                        // The depth test decides which end is the nearest
+                        let t = if max > min { (depth - min) / (max - min) } else { 1.0 };
+                        let t = if self.depth_compare == DepthCompare::Greater { t } else { 1.0 - t };
+                        let v = (t * 255.0).round() as u8;
+
+                        rgba.extend_from_slice(&[v, v, v, 0xff]);
+                    },
+                    None => rgba.extend_from_slice(&[0x00; 4]),
+                }
+            }
+        }
+
+        rgba
+    }
+
+    /// Colors of all pixels as rgba bytes, supersampled screens need to be resolved first
     pub fn get_slice(&self) -> &[u8] {
+        let color_slice = if self.samples > 1 { &self.resolved } else { &self.color };
+
         // Flatten the 2D array of [u8; 4] to a 1D slice of u8
-        let color_slice = &self.color;
         let flattened_slice = unsafe {
             std::slice::from_raw_parts(
                 color_slice.as_ptr() as *const u8,
@@ -169,13 +426,146 @@ impl Screen {
     }
 }
 
+/// Point on the screen after projection, with the depth and color used to draw it
+#[derive(Clone, Copy, Debug)]
+pub struct Vertex {
+    pub pos: Pos2D,
+    pub depth: f32,
+    pub color: Color,
+}
+
+/// Shape to be drawn onto a canvas
+#[derive(Clone, Copy, Debug)]
+pub enum Primitive {
+    /// Square point with a radius in tenths of a pixel
+    Point(Vertex, usize),
+    /// Line with a radius in tenths of a pixel
+    Line(Vertex, Vertex, usize),
+    /// Filled triangle, colors and depths are interpolated between the vertices
+    Triangle(Vertex, Vertex, Vertex),
+}
+
+impl Primitive {
+    pub fn draw<C: Canvas + ?Sized>(&self, canvas: &mut C) {
+        match *self {
+            Primitive::Point(a, r) => canvas.draw_point(a, r),
+            Primitive::Line(a, b, r) => canvas.draw_line(a, b, r),
+            Primitive::Triangle(a, b, c) => canvas.draw_triangle(a, b, c),
+        }
+    }
+}
+
+/// Something projected primitives can be drawn onto, like the pixelbuffer or a vector image
+pub trait Canvas {
+    fn size(&self) -> (usize, usize);
+
+    fn draw_point(&mut self, a: Vertex, r: usize);
+
+    fn draw_line(&mut self, a: Vertex, b: Vertex, r: usize);
+
+    fn draw_triangle(&mut self, a: Vertex, b: Vertex, c: Vertex);
+}
+
+impl Canvas for Screen {
+    fn size(&self) -> (usize, usize) {
+        Screen::size(self)
+    }
+
+    fn draw_point(&mut self, a: Vertex, r: usize) {
+        if a.pos.x < 0.0 || a.pos.y < 0.0 { return; }
+
+        let samples = self.samples as f32;
+
+        print_point((a.pos.x * samples) as usize, (a.pos.y * samples) as usize, r * self.samples, self, a.color, a.depth);
+    }
+
+    fn draw_line(&mut self, a: Vertex, b: Vertex, r: usize) {
+        // Stamp a point on every sample along the line
+        let steps = ((b.pos - a.pos).len() * self.samples as f32).ceil().max(1.0) as usize;
+
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+
+            self.draw_point(Vertex {
+                pos: a.pos + (b.pos - a.pos) * t,
+                depth: a.depth + (b.depth - a.depth) * t,
+                color: lerp_color(a.color, b.color, t),
+            }, r);
+        }
+    }
+
+    fn draw_triangle(&mut self, a: Vertex, b: Vertex, c: Vertex) {
+        let (width, height) = self.sample_size();
+
+        // Rasterize in sample coordinates
+        let samples = self.samples as f32;
+        let a = Vertex { pos: a.pos * samples, ..a };
+        let b = Vertex { pos: b.pos * samples, ..b };
+        let c = Vertex { pos: c.pos * samples, ..c };
+
+        // Twice the signed area, used to normalize the barycentric coordinates
+        let area = edge_function(a.pos, b.pos, c.pos);
+        if area == 0.0 { return; }
+
+        // Only visit the pixels inside the bounding box of the triangle
+        let min_x = a.pos.x.min(b.pos.x).min(c.pos.x).floor().max(0.0) as usize;
+        let min_y = a.pos.y.min(b.pos.y).min(c.pos.y).floor().max(0.0) as usize;
+        let max_x = (a.pos.x.max(b.pos.x).max(c.pos.x).ceil().max(0.0) as usize).min(width.saturating_sub(1));
+        let max_y = (a.pos.y.max(b.pos.y).max(c.pos.y).ceil().max(0.0) as usize).min(height.saturating_sub(1));
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // Sample the center of the sample
+                let p = Pos2D { x: x as f32 + 0.5, y: y as f32 + 0.5 };
+
+                let u = edge_function(b.pos, c.pos, p) / area;
+                let v = edge_function(c.pos, a.pos, p) / area;
+                let w = edge_function(a.pos, b.pos, p) / area;
+
+                // Make sure it is a point on the triangle
+                if u < 0.0 || v < 0.0 || w < 0.0 { continue; }
+
+                let a_color = a.color.get_rgba();
+                let b_color = b.color.get_rgba();
+                let c_color = c.color.get_rgba();
+
+                let mut rgba: [u8; 4] = [0; 4];
+                for i in 0..4 {
+                    rgba[i] = (a_color[i] as f32 * u + b_color[i] as f32 * v + c_color[i] as f32 * w) as u8;
+                }
+
+                let depth = a.depth * u + b.depth * v + c.depth * w;
+
+                let _ = self.write(x, y, Color::RGBA(rgba[0], rgba[1], rgba[2], rgba[3]), depth);
+            }
+        }
+    }
+}
+
+/// Signed area of the parallelogram spanned by a -> b and a -> p
+fn edge_function(a: Pos2D, b: Pos2D, p: Pos2D) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let a = a.get_rgba();
+    let b = b.get_rgba();
+
+    let mut rgba: [u8; 4] = [0; 4];
+    for i in 0..4 {
+        rgba[i] = (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t) as u8;
+    }
+
+    Color::RGBA(rgba[0], rgba[1], rgba[2], rgba[3])
+}
+
 pub trait Render<P, PN, P2, P3> 
 where 
     PN: Project2D<Output = (P2, f32)> + Project3D<Output = P3>,
 {
     type Output;
 
-    /// Determine the screen coordinates of objects using certain transformations and insert them into the pixelbuffer
+    /// Determine the screen coordinates of objects using certain transformations and turn them into primitives
     fn draw(
         &self,
         positions: &[P],
@@ -188,7 +578,7 @@ impl<T> Render<Node<T>, T, Pos2D, Pos3D> for Node<T>
 where 
     T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Copy, 
 {
-    type Output = Vec<(Pos2D, usize, Color, f32)>;
+    type Output = Vec<Primitive>;
     
     fn draw(
         &self,
@@ -206,18 +596,18 @@ where
 
         let r = self.r; //scale(self.pos, projection.get_camera_pos()) * self.r;
 
-        changes.push((pos, r, self.color, depth));
+        changes.push(Primitive::Point(Vertex { pos, depth, color: self.color }, r));
 
         changes
     }
 }
 
-impl<T> Render<Node<T>, T, Pos2D, Pos3D> for Face 
-where 
+impl<T> Render<Node<T>, T, Pos2D, Pos3D> for Edge
+where
     T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Copy,
 {
-    type Output = Vec<(Pos2D, usize, Color, f32)>;
-    
+    type Output = Vec<Primitive>;
+
     fn draw(
         &self,
         nodes: &[Node<T>],
@@ -227,6 +617,27 @@ where
         let mut changes = Vec::new();
 
         if self.r == 0 { return changes; }
+
+        let [a, b] = [self.node_a_index, self.node_b_index].map(|index| {
+            let node = &nodes[index];
+            let (pos, depth) = projection.project(node.pos, screen_size);
+
+            Vertex { pos, depth, color: node.color }
+        });
+
+        changes.push(Primitive::Line(a, b, self.r));
+
+        changes
+    }
+}
+
+impl Face {
+    /// Cosine of the angle between the normal of the face and the direction of the camera
+    /// 1 if staight on, 0 if perpendicular and -1 if facing opposite
+    pub fn angle_to_camera<T>(&self, nodes: &[Node<T>], screen_size: (usize, usize), projection: Projection) -> f32
+    where
+        T: Project3D<Output = Pos3D> + Copy,
+    {
         let node_a = &nodes[self.node_a_index];
         let node_b = &nodes[self.node_b_index];
         let node_c = &nodes[self.node_c_index];
@@ -239,62 +650,68 @@ where
         // Get the normal vector of the surface by taking the cross product
         let normal = vector_a ^ vector_b;
 
-        let to_camera = projection.get_camera_pos();
-
-        // Let the brightness depend on the angle between the normal and the camera path
-        // 1 if staight on, 0 if perpendicular and -1 if facing opposite
-        let angle_to_camera = (normal >> to_camera) / (normal.len() * to_camera.len());
-
-        if angle_to_camera < 0.0 { return changes; }
-
-        // Get the locations of the three nodes of the triangle
-        let (pos_a, depth_a) = projection.project(node_a.pos, screen_size);
-        let (pos_b, depth_b) = projection.project(node_b.pos, screen_size);
-        let (pos_c, depth_c) = projection.project(node_c.pos, screen_size);
-
-        // Calculate 2d vectors between the points on the screen
-        let a_to_b = pos_b + (pos_a * -1.0);
-        let a_to_c = pos_c + (pos_a * -1.0);
+        // Look from the center of the face, the direction differs per face for perspective projection
+        let center = (node_a.pos.project_3d(&projection, screen_size)
+            + node_b.pos.project_3d(&projection, screen_size)
+            + node_c.pos.project_3d(&projection, screen_size)) * (1.0 / 3.0);
+        let to_camera = projection.to_camera(center);
 
-        // Change the alpha channel based on the angle between the camera and the surface
-        let alpha = (255.0 * angle_to_camera.clamp(0.0, 1.0)) as u8;
+        (normal >> to_camera) / (normal.len() * to_camera.len())
+    }
+}
 
-        // Get the colors from the three nodes of the face
-        let a_color = node_a.color.get_rgba();
-        let b_color = node_b.color.get_rgba();
-        let c_color = node_c.color.get_rgba();
+impl<T> Render<Node<T>, T, Pos2D, Pos3D> for Face 
+where 
+    T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Copy,
+{
+    type Output = Vec<Primitive>;
+    
+    fn draw(
+        &self,
+        nodes: &[Node<T>],
+        screen_size: (usize, usize),
+        projection: Projection,
+    ) -> Self::Output {
+        self.draw_culled(nodes, screen_size, projection, CullMode::Back)
+    }
+}
 
-        // Calculate triangle fill resolution based on angle to camera and length of edges
-        let resolution: f32 = 0.2 * angle_to_camera.clamp(0.001, 1.0);
-        let u_res: f32 = a_to_b.len() * resolution;
-        let v_res: f32 = a_to_c.len() * resolution;
+impl Face {
+    /// Draw the face unless the cull mode skips the side facing the camera
+    pub fn draw_culled<T>(
+        &self,
+        nodes: &[Node<T>],
+        screen_size: (usize, usize),
+        projection: Projection,
+        cull: CullMode,
+    ) -> Vec<Primitive>
+    where
+        T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Copy,
+    {
+        let mut changes = Vec::new();
 
-        // Amount of offset to add between the edges of the faces to avoid overlap
-        let edge_offset = 0.35;
+        if self.r == 0 { return changes; }
 
-        // Iterate over points on the surface of the face and print them to the screen
-        for k1 in 0..=(u_res as i32) {
-            for k2 in 0..=(v_res as i32) {
-                let u = (k1 as f32 + edge_offset) / u_res;
-                let v = (k2 as f32 + edge_offset) / v_res;
+        let angle_to_camera = self.angle_to_camera(nodes, screen_size, projection);
 
-                // Make sure it is a point on the triangle
-                if u + v > 1.0 {break;}
+        if !cull.keeps(angle_to_camera) { return changes; }
 
-                let mut rgba: [u8; 4] = [0; 4];
-                for c in 0..4 {
-                    rgba[c] = (a_color[c] as f32 + (b_color[c] as f32 - a_color[c] as f32) * u + (c_color[c] as f32 - a_color[c] as f32) * v) as u8
-                }
+        // Let the brightness depend on the angle between the normal and the camera path, for either side
+        let angle_to_camera = angle_to_camera.abs();
 
-                let color = Color::RGBA(rgba[0], rgba[1], rgba[2], alpha);
+        // Get the locations and colors of the three nodes of the triangle
+        let [a, b, c] = [self.node_a_index, self.node_b_index, self.node_c_index].map(|index| {
+            let node = &nodes[index];
+            let (pos, depth) = projection.project(node.pos, screen_size);
+            let [r, g, b, a] = node.color.get_rgba();
 
-                let pos = pos_a + a_to_b * u + a_to_c * v;
+            // Change the alpha channel based on the angle between the camera and the surface
+            let alpha = (a as f32 * angle_to_camera.clamp(0.0, 1.0)) as u8;
 
-                let depth = depth_a + (depth_b - depth_a) * u + (depth_c - depth_a) * v;
+            Vertex { pos, depth, color: Color::RGBA(r, g, b, alpha) }
+        });
 
-                changes.push((pos, self.r, color, depth));
-            }
-        }
+        changes.push(Primitive::Triangle(a, b, c));
 
         changes
     }
@@ -0,0 +1,357 @@
+use std::ops;
+
+use crate::{matrix::Matrix4x4, pos::{Len, Pos3D, Pos4D, RotationPlane}};
+
+// Basis blades of the 4D geometric algebra are stored as bitmasks, x = 0b0001 .. w = 0b1000
+const X: usize = 0b0001;
+const Y: usize = 0b0010;
+const Z: usize = 0b0100;
+const W: usize = 0b1000;
+
+/// Oriented plane with a magnitude, e.g. a rotation angle or an angular velocity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bivector {
+    pub xy: f32,
+    pub xz: f32,
+    pub xw: f32,
+    pub yz: f32,
+    pub yw: f32,
+    pub zw: f32,
+}
+
+impl Bivector {
+    pub fn new(xy: f32, xz: f32, xw: f32, yz: f32, yw: f32, zw: f32) -> Self {
+        Self { xy, xz, xw, yz, yw, zw }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(0.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Outer product `a ^ b` of two vectors, the plane spanned by them oriented from a to b
+    pub fn wedge(a: Pos4D, b: Pos4D) -> Self {
+        Self {
+            xy: a.x * b.y - a.y * b.x,
+            xz: a.x * b.z - a.z * b.x,
+            xw: a.x * b.w - a.w * b.x,
+            yz: a.y * b.z - a.z * b.y,
+            yw: a.y * b.w - a.w * b.y,
+            zw: a.z * b.w - a.w * b.z,
+        }
+    }
+
+    /// Outer product of two 3D vectors, the bivector will not have any w components
+    pub fn wedge_3d(a: Pos3D, b: Pos3D) -> Self {
+        Self::wedge(a.into(), b.into())
+    }
+
+    /// Scale the bivector to unit magnitude, keeping its plane and orientation
+    pub fn normalize(&self) -> Self {
+        let len = self.len();
+
+        if len == 0.0 {
+            return *self;
+        }
+
+        *self * (1.0 / len)
+    }
+
+    fn to_multivector(self) -> [f32; 16] {
+        let mut m = [0.0; 16];
+        m[X | Y] = self.xy;
+        m[X | Z] = self.xz;
+        m[X | W] = self.xw;
+        m[Y | Z] = self.yz;
+        m[Y | W] = self.yw;
+        m[Z | W] = self.zw;
+        m
+    }
+
+    fn from_multivector(m: &[f32; 16]) -> Self {
+        Self { xy: m[X | Y], xz: m[X | Z], xw: m[X | W], yz: m[Y | Z], yw: m[Y | W], zw: m[Z | W] }
+    }
+}
+
+impl From<RotationPlane> for Bivector {
+    fn from(val: RotationPlane) -> Self {
+        use RotationPlane::*;
+
+        match val {
+            XY => Self::new(1.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            XZ => Self::new(0.0, 1.0, 0.0, 0.0, 0.0, 0.0),
+            XW => Self::new(0.0, 0.0, 1.0, 0.0, 0.0, 0.0),
+            YZ => Self::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0),
+            YW => Self::new(0.0, 0.0, 0.0, 0.0, 1.0, 0.0),
+            ZW => Self::new(0.0, 0.0, 0.0, 0.0, 0.0, 1.0),
+            YX => Self::new(-1.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            ZX => Self::new(0.0, -1.0, 0.0, 0.0, 0.0, 0.0),
+            WX => Self::new(0.0, 0.0, -1.0, 0.0, 0.0, 0.0),
+            ZY => Self::new(0.0, 0.0, 0.0, -1.0, 0.0, 0.0),
+            WY => Self::new(0.0, 0.0, 0.0, 0.0, -1.0, 0.0),
+            WZ => Self::new(0.0, 0.0, 0.0, 0.0, 0.0, -1.0),
+        }
+    }
+}
+
+impl Len for Bivector {
+    fn len(&self) -> f32 {
+        (self.xy.powi(2) + self.xz.powi(2) + self.xw.powi(2) + self.yz.powi(2) + self.yw.powi(2) + self.zw.powi(2)).sqrt()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0.0
+    }
+}
+
+impl ops::Add for Bivector {
+    type Output = Bivector;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::Output {
+            xy: self.xy + rhs.xy,
+            xz: self.xz + rhs.xz,
+            xw: self.xw + rhs.xw,
+            yz: self.yz + rhs.yz,
+            yw: self.yw + rhs.yw,
+            zw: self.zw + rhs.zw,
+        }
+    }
+}
+
+impl ops::Mul<f32> for Bivector {
+    type Output = Bivector;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self::Output {
+            xy: self.xy * rhs,
+            xz: self.xz * rhs,
+            xw: self.xw * rhs,
+            yz: self.yz * rhs,
+            yw: self.yw * rhs,
+            zw: self.zw * rhs,
+        }
+    }
+}
+
+/// Even element of the 4D geometric algebra rotating vectors using `R * v * ~R`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rotor {
+    pub s: f32,
+    pub b: Bivector,
+    pub xyzw: f32,
+}
+
+impl Rotor {
+    pub fn identity() -> Self {
+        Self { s: 1.0, b: Bivector::zero(), xyzw: 0.0 }
+    }
+
+    /// Rotate by `|bivector|` radians in the plane(s) of the bivector, turning x towards y for `xy`
+    ///
+    /// A general 4D bivector describes two simultaneous rotations in orthogonal planes,
+    /// both of which are applied.
+    pub fn from_bivector(bivector: Bivector) -> Self {
+        exp(bivector * -0.5)
+    }
+
+    /// Rotate by `angle` radians in `plane`, the magnitude of the plane is ignored
+    pub fn from_plane(plane: Bivector, angle: f32) -> Self {
+        Self::from_bivector(plane.normalize() * angle)
+    }
+
+    /// Rotation after spinning with angular velocity `omega` (radians per second) for `dt` seconds
+    pub fn from_angular_velocity(omega: Bivector, dt: f32) -> Self {
+        Self::from_bivector(omega * dt)
+    }
+
+    /// Inverse of the bivector exponential, `Rotor::from_bivector(r.log()) == r`
+    pub fn log(&self) -> Bivector {
+        log(self) * -2.0
+    }
+
+    /// Reverse `~R`, the inverse rotation for unit rotors
+    pub fn reverse(&self) -> Self {
+        Self { s: self.s, b: self.b * -1.0, xyzw: self.xyzw }
+    }
+
+    /// Scale the rotor back to unit length to remove accumulated rounding errors
+    pub fn normalize(&self) -> Self {
+        let len = (self.s.powi(2) + self.b.len().powi(2) + self.xyzw.powi(2)).sqrt();
+
+        if len == 0.0 {
+            return Self::identity();
+        }
+
+        Self { s: self.s / len, b: self.b * (1.0 / len), xyzw: self.xyzw / len }
+    }
+
+    /// Interpolate between two rotations along the shortest path
+    pub fn slerp(&self, other: Rotor, t: f32) -> Self {
+        let mut delta = self.reverse() * other;
+
+        // R and -R describe the same rotation
+        if delta.s < 0.0 {
+            delta = Self { s: -delta.s, b: delta.b * -1.0, xyzw: -delta.xyzw };
+        }
+
+        (*self * Self::from_bivector(delta.log() * t)).normalize()
+    }
+
+    fn to_multivector(self) -> [f32; 16] {
+        let mut m = self.b.to_multivector();
+        m[0] = self.s;
+        m[X | Y | Z | W] = self.xyzw;
+        m
+    }
+
+    fn from_multivector(m: &[f32; 16]) -> Self {
+        Self { s: m[0], b: Bivector::from_multivector(m), xyzw: m[X | Y | Z | W] }
+    }
+}
+
+// Composition, rhs is applied first, the same as for matrices
+impl ops::Mul for Rotor {
+    type Output = Rotor;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Rotor::from_multivector(&product(&self.to_multivector(), &rhs.to_multivector()))
+    }
+}
+
+impl ops::Mul<Pos4D> for Rotor {
+    type Output = Pos4D;
+
+    fn mul(self, rhs: Pos4D) -> Self::Output {
+        let mut v = [0.0; 16];
+        v[X] = rhs.x;
+        v[Y] = rhs.y;
+        v[Z] = rhs.z;
+        v[W] = rhs.w;
+
+        let r = product(&product(&self.to_multivector(), &v), &self.reverse().to_multivector());
+
+        Pos4D { x: r[X], y: r[Y], z: r[Z], w: r[W] }
+    }
+}
+
+// Rotors with w components will move points out of the xyz space, those are projected back
+impl ops::Mul<Pos3D> for Rotor {
+    type Output = Pos3D;
+
+    fn mul(self, rhs: Pos3D) -> Self::Output {
+        (self * Pos4D::from(rhs)).into()
+    }
+}
+
+impl From<Rotor> for Matrix4x4 {
+    fn from(val: Rotor) -> Self {
+        let val = val.normalize();
+
+        // The columns of the matrix are the images of the basis vectors
+        let x = val * Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 };
+        let y = val * Pos4D { x: 0.0, y: 1.0, z: 0.0, w: 0.0 };
+        let z = val * Pos4D { x: 0.0, y: 0.0, z: 1.0, w: 0.0 };
+        let w = val * Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 1.0 };
+
+        Matrix4x4::new([
+            [x.x, y.x, z.x, w.x],
+            [x.y, y.y, z.y, w.y],
+            [x.z, y.z, z.z, w.z],
+            [x.w, y.w, z.w, w.w],
+        ])
+    }
+}
+
+/// Sign picked up by reordering the product of two basis blades into canonical order
+fn reorder_sign(a: usize, b: usize) -> f32 {
+    let mut a = a >> 1;
+    let mut swaps = 0;
+
+    while a != 0 {
+        swaps += (a & b).count_ones();
+        a >>= 1;
+    }
+
+    if swaps % 2 == 0 { 1.0 } else { -1.0 }
+}
+
+/// Geometric product of two multivectors of the euclidean 4D algebra
+fn product(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+    let mut result = [0.0; 16];
+
+    for (i, &a_i) in a.iter().enumerate() {
+        if a_i == 0.0 { continue; }
+
+        for (j, &b_j) in b.iter().enumerate() {
+            if b_j == 0.0 { continue; }
+
+            result[i ^ j] += reorder_sign(i, j) * a_i * b_j;
+        }
+    }
+
+    result
+}
+
+// The pseudoscalar I squares to 1 and commutes with even elements, so P = (1 + I) / 2 and
+// Q = (1 - I) / 2 split every bivector into two commuting parts that each square to a
+// scalar multiple of their idempotent. These are the self dual and anti self dual halves.
+fn split(m: &[f32; 16]) -> ([f32; 16], [f32; 16]) {
+    let mut i = [0.0; 16];
+    i[X | Y | Z | W] = 1.0;
+
+    let dual = product(&i, m);
+
+    let mut p = [0.0; 16];
+    let mut q = [0.0; 16];
+    for k in 0..16 {
+        p[k] = (m[k] + dual[k]) / 2.0;
+        q[k] = (m[k] - dual[k]) / 2.0;
+    }
+
+    (p, q)
+}
+
+/// Bivector exponential
+fn exp(bivector: Bivector) -> Rotor {
+    let (p, q) = split(&bivector.to_multivector());
+
+    let mut result = [0.0; 16];
+
+    for (part, sign) in [(p, 1.0), (q, -1.0)] {
+        // part^2 = -angle^2 * (1 + sign * I) / 2
+        let square = product(&part, &part);
+        let angle = (-(square[0] + sign * square[X | Y | Z | W])).max(0.0).sqrt();
+
+        let sinc = if angle < 1e-6 { 1.0 } else { angle.sin() / angle };
+
+        result[0] += angle.cos() / 2.0;
+        result[X | Y | Z | W] += sign * angle.cos() / 2.0;
+
+        for k in 0..16 {
+            result[k] += part[k] * sinc;
+        }
+    }
+
+    Rotor::from_multivector(&result)
+}
+
+/// Bivector logarithm of a unit rotor
+fn log(rotor: &Rotor) -> Bivector {
+    let (p, q) = split(&rotor.b.to_multivector());
+
+    let mut result = Bivector::zero();
+
+    for (part, sign) in [(p, 1.0), (q, -1.0)] {
+        let cos = rotor.s + sign * rotor.xyzw;
+
+        let square = product(&part, &part);
+        let sin = (-(square[0] + sign * square[X | Y | Z | W])).max(0.0).sqrt();
+
+        let angle = sin.atan2(cos);
+        let scale = if sin < 1e-6 { 1.0 } else { angle / sin };
+
+        result = result + Bivector::from_multivector(&part) * scale;
+    }
+
+    result
+}
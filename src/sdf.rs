@@ -0,0 +1,253 @@
+use std::thread;
+
+use crate::{
+    matrix::Matrix4x4,
+    pos::{Len, Pos3D, Pos4D},
+    render::{Color, Screen},
+    rotor::Rotor,
+};
+
+/// Signed distance field of a 4D scene, negative inside
+#[derive(Clone)]
+pub enum Sdf {
+    Hypersphere { center: Pos4D, r: f32 },
+    /// Axis aligned tesseract reaching `r` from its center along every axis, like `create_4_cube`
+    Tesseract { center: Pos4D, r: f32 },
+    /// Clifford torus on the 3-sphere of radius `r` around the origin, thickened by `thickness`
+    CliffordTorus { r: f32, thickness: f32 },
+    Union(Box<Sdf>, Box<Sdf>),
+    Intersection(Box<Sdf>, Box<Sdf>),
+    /// Union blending the two surfaces where they are closer than `k`
+    SmoothUnion(Box<Sdf>, Box<Sdf>, f32),
+    /// The field rotated around the origin, with the matrix rotating positions back into the field
+    Rotated(Box<Sdf>, Matrix4x4),
+}
+
+impl Sdf {
+    /// Rotate the field around the origin
+    pub fn rotated(self, rotor: Rotor) -> Self {
+        Sdf::Rotated(Box::new(self), Matrix4x4::from(rotor.reverse()))
+    }
+
+    /// Distance to the closest surface, never more than the real distance so rays can step it safely
+    pub fn distance(&self, pos: Pos4D) -> f32 {
+        match self {
+            Sdf::Hypersphere { center, r } => (pos - *center).len() - r,
+            Sdf::Tesseract { center, r } => {
+                let p = pos - *center;
+                let q = [p.x, p.y, p.z, p.w].map(|c| c.abs() - r);
+
+                let outside = q.iter().map(|c| c.max(0.0).powi(2)).sum::<f32>().sqrt();
+                let inside = q.iter().fold(f32::MIN, |max, &c| max.max(c)).min(0.0);
+
+                outside + inside
+            }
+            Sdf::CliffordTorus { r, thickness } => {
+                let r = r / 2.0_f32.sqrt();
+                let a = (pos.x * pos.x + pos.y * pos.y).sqrt() - r;
+                let b = (pos.z * pos.z + pos.w * pos.w).sqrt() - r;
+
+                (a * a + b * b).sqrt() - thickness
+            }
+            Sdf::Union(a, b) => a.distance(pos).min(b.distance(pos)),
+            Sdf::Intersection(a, b) => a.distance(pos).max(b.distance(pos)),
+            Sdf::SmoothUnion(a, b, k) => {
+                let (a, b) = (a.distance(pos), b.distance(pos));
+                let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+
+                b + (a - b) * h - k * h * (1.0 - h)
+            }
+            Sdf::Rotated(sdf, inverse) => sdf.distance(*inverse * pos),
+        }
+    }
+
+    /// Unit normal of the surface through a position, the gradient of the field
+    pub fn normal(&self, pos: Pos4D) -> Pos4D {
+        const E: f32 = 1e-3;
+
+        let axes = [
+            Pos4D { x: E, y: 0.0, z: 0.0, w: 0.0 },
+            Pos4D { x: 0.0, y: E, z: 0.0, w: 0.0 },
+            Pos4D { x: 0.0, y: 0.0, z: E, w: 0.0 },
+            Pos4D { x: 0.0, y: 0.0, z: 0.0, w: E },
+        ];
+        let [x, y, z, w] = axes.map(|axis| self.distance(pos + axis) - self.distance(pos - axis));
+        let gradient = Pos4D { x, y, z, w };
+
+        let len = gradient.len();
+        if len > 0.0 { gradient / len } else { gradient }
+    }
+}
+
+/// 3D space the rays of the ray marcher are cast in
+#[derive(Clone, Copy, Debug)]
+pub enum MarchMode {
+    /// The hyperplane w = `w` through the scene
+    Slice(f32),
+    /// The 3D image a 4D eye at w = -2 sees, like `ProjectionType::Stereographic`,
+    /// with every point colored by the nearest surface along its 4D ray
+    Projection,
+}
+
+/// Renders a signed distance field by stepping along rays, as an alternative to drawing meshes
+///
+/// The 3D eye sits at the same place as the eye of `ProjectionType::Perspective`.
+#[derive(Clone, Copy, Debug)]
+pub struct RayMarcher {
+    pub mode: MarchMode,
+    /// Size of the view, like the scale of the projection
+    pub scale: f32,
+    /// Radius around the origin the scene fits in, or its 3D image when projecting
+    pub bounds: f32,
+    pub color: Color,
+    /// Direction towards the light
+    pub light: Pos4D,
+    /// Brightness of surfaces facing away from the light
+    pub ambient: f32,
+    /// Steps along a ray before it counts as a miss
+    pub max_steps: usize,
+}
+
+impl RayMarcher {
+    pub fn new(mode: MarchMode, scale: f32) -> Self {
+        Self {
+            mode,
+            scale,
+            bounds: 2.0 * scale,
+            color: Color::Purple,
+            // From the eye, above the screen and towards the 4D eye
+            light: Pos4D { x: -1.0, y: -1.0, z: 0.5, w: -0.5 },
+            ambient: 0.15,
+            max_steps: 128,
+        }
+    }
+
+    /// Ray march every sample of the screen
+    pub fn render(&self, sdf: &Sdf, screen: &mut Screen) {
+        let (width, height) = screen.sample_size();
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let rows = height.div_ceil(threads).max(1);
+
+        // Split the rows between threads, every thread returns the hits of its rows in order
+        let hits: Vec<Vec<Option<(Color, f32)>>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..height).step_by(rows).map(|start| {
+                scope.spawn(move || {
+                    (start..(start + rows).min(height))
+                        .flat_map(|y| (0..width).map(move |x| self.sample(sdf, x, y, (width, height))))
+                        .collect()
+                })
+            }).collect();
+
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        for (index, hit) in hits.into_iter().flatten().enumerate() {
+            if let Some((color, depth)) = hit {
+                screen.write(index % width, index / width, color, depth).ok();
+            }
+        }
+    }
+
+    /// Color and depth of the surface seen through a sample
+    fn sample(&self, sdf: &Sdf, x: usize, y: usize, size: (usize, usize)) -> Option<(Color, f32)> {
+        let eye = Pos3D { x: -3.0 * self.scale, y: 0.0, z: 0.0 };
+
+        // Half the view is `scale / 0.9` wide at the origin, which matches the perspective projection
+        let bound = size.0.min(size.1) as f32 / 2.0;
+        let u = (x as f32 + 0.5 - size.0 as f32 / 2.0) / bound;
+        let v = (y as f32 + 0.5 - size.1 as f32 / 2.0) / bound;
+        let direction = Pos3D { x: 3.0 * self.scale, y: v * self.scale / 0.9, z: -u * self.scale / 0.9 };
+        let direction = direction / direction.len();
+
+        let (enter, exit) = sphere_span(eye, direction, self.bounds)?;
+        let far = 3.0 * self.scale + self.bounds;
+
+        let (t, pos) = match self.mode {
+            MarchMode::Slice(w) => {
+                let to_4d = |t: f32| Pos4D { w, ..(eye + direction * t).into() };
+                let t = self.march(sdf, enter, exit, to_4d).ok()?;
+
+                (t, to_4d(t))
+            }
+            MarchMode::Projection => {
+                let eye_4d = Pos4D { x: 0.0, y: 0.0, z: 0.0, w: -2.0 };
+                let reach = 2.0 + self.bounds;
+
+                let mut t = enter;
+                let mut found = None;
+                for _ in 0..self.max_steps {
+                    let image = eye + direction * t;
+                    let direction_4d = Pos4D { w: 1.0, ..image.into() };
+                    let len = direction_4d.len();
+                    let ray = |s: f32| eye_4d + direction_4d * (s / len);
+
+                    match self.march(sdf, 0.0, reach, ray) {
+                        Ok(s) => {
+                            found = Some((t, ray(s)));
+                            break;
+                        }
+                        // Rays of nearby points in the image turn by at most the distance divided by `len`,
+                        // so they pass the surface at most that angle times `reach` closer
+                        Err(closest) => t += (closest * len / reach).max(self.scale * 1e-3),
+                    }
+
+                    if t > exit {
+                        break;
+                    }
+                }
+
+                found?
+            }
+        };
+
+        // Lambert shading with some ambient light so the unlit side keeps its shape
+        let light = self.light / self.light.len();
+        let brightness = self.ambient + (1.0 - self.ambient) * (sdf.normal(pos) >> light).max(0.0);
+        let [r, g, b, a] = self.color.to_linear();
+
+        Some((Color::from_linear([r * brightness, g * brightness, b * brightness, a]), (t / far).clamp(0.0, 1.0)))
+    }
+
+    /// Step along a ray between `from` and `to` by the distance to the closest surface
+    ///
+    /// Returns where the ray hits, or how close it got to a surface when it misses.
+    fn march<F>(&self, sdf: &Sdf, from: f32, to: f32, ray: F) -> Result<f32, f32>
+    where
+        F: Fn(f32) -> Pos4D,
+    {
+        let epsilon = self.scale * 1e-3;
+        let mut t = from;
+        let mut closest = f32::MAX;
+
+        for _ in 0..self.max_steps {
+            let distance = sdf.distance(ray(t));
+            if distance < epsilon {
+                return Ok(t);
+            }
+
+            closest = closest.min(distance);
+            t += distance;
+            if t > to {
+                break;
+            }
+        }
+
+        Err(closest)
+    }
+}
+
+/// Distances along a ray where it enters and leaves a sphere around the origin, starting at 0 when inside
+fn sphere_span(origin: Pos3D, direction: Pos3D, r: f32) -> Option<(f32, f32)> {
+    let b = origin >> direction;
+    let c = (origin >> origin) - r * r;
+    let discriminant = b * b - c;
+
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    let exit = -b + root;
+
+    if exit < 0.0 { None } else { Some(((-b - root).max(0.0), exit)) }
+}
@@ -1,9 +1,14 @@
-use std::f32::consts::PI;
+use std::{collections::HashMap, f32::consts::PI};
 
 use crate::{
-    pos::{Pos3D, Pos4D},
+    complex::{complex_graph, polar_graph, Complex, Domain},
+    fractal::{fractal_points, Fractal},
+    hopf::{hopf_fibers, sample_curve, FiberStyle},
+    parametric::{parametric_surface, parametric_surface_4d, parametric_volume, Patch},
+    pos::{Len, Pos3D, Pos4D},
+    quaternion::Quaternion,
     render::Color::*,
-    object::{Face, Node, Object},
+    object::{Cell, CullMode, Edge, Face, Node, Object},
 };
 
 pub fn empty_3d() -> Object<Pos3D> {
@@ -48,10 +53,7 @@ pub fn empty_3d() -> Object<Pos3D> {
 
     let faces: Vec<Face> = Vec::new();
 
-    Object {
-        nodes,
-        faces,
-    }
+    Object::new(nodes, faces)
 }
 
 pub fn empty_4d() -> Object<Pos4D> {
@@ -110,10 +112,7 @@ pub fn empty_4d() -> Object<Pos4D> {
 
     let faces: Vec<Face> = Vec::new();
 
-    Object {
-        nodes,
-        faces,
-    }
+    Object::new(nodes, faces)
 }
 
 pub fn create_3_cube(r: f32) -> Object<Pos3D> {
@@ -162,10 +161,7 @@ pub fn create_3_cube(r: f32) -> Object<Pos3D> {
         })
     }
 
-    Object {
-        nodes,
-        faces,
-    }
+    Object::new(nodes, faces)
 }
 
 pub fn create_4_cube(r: f32) -> Object<Pos4D> {
@@ -190,183 +186,347 @@ pub fn create_4_cube(r: f32) -> Object<Pos4D> {
         }
     }
 
-    const FACE_INDECES: [(usize, usize, usize); 48] = [
-        (0, 1, 4),
-        (0, 4, 2),
-        (0, 2, 1),
-        (0, 8, 11),
-        (0, 11, 13),
-        (0, 13, 8),
-
-        (3, 1, 2),
-        (3, 2, 7),
-        (3, 7, 1),
-        (3, 8, 11),
-        (3, 11, 14),
-        (3, 14, 8),
-
-        (5, 1, 7),
-        (5, 4, 1),
-        (5, 7, 4),
-        (5, 8, 13),
-        (5, 13, 14),
-        (5, 14, 8),
-
-        (6, 2, 4),
-        (6, 4, 7),
-        (6, 7, 2),
-        (6, 11, 13),
-        (6, 13, 14), 
-        (6, 14, 11),
-
-        (9, 8, 11),
-        (9, 11, 2),
-        (9, 2, 1),
-        (9, 8, 11),
-        (9, 11, 13),
-        (9, 13, 8),
-
-        (10, 1, 2),
-        (10, 2, 7),
-        (10, 7, 1),
-        (10, 8, 11),
-        (10, 11, 14),
-        (10, 14, 8),
-
-        (11, 1, 7),
-        (11, 4, 1),
-        (11, 7, 4),
-        (11, 8, 13),
-        (11, 13, 14),
-        (11, 14, 8),
-
-        (13, 2, 4),
-        (13, 4, 7),
-        (13, 7, 2),
-        (13, 11, 13),
-        (13, 13, 14),
-        (13, 14, 11),
-    ];
+    // Every pair of axes spans a square for each combination of the other two, with the index bits x=8, y=4, z=2 and w=1
     let mut faces = Vec::new();
-    for index in FACE_INDECES.iter() {
-        faces.push(Face {
-            node_a_index: index.0,
-            node_b_index: index.1,
-            node_c_index: index.2,
-            r: 5,
-        })
+    for (a, b) in [(8, 4), (8, 2), (8, 1), (4, 2), (4, 1), (2, 1)] {
+        for base in (0..16).filter(|base| base & (a | b) == 0) {
+            let corners = [base, base + a, base + a + b, base + b];
+
+            for (p, q) in [(1, 2), (2, 3)] {
+                faces.push(Face {
+                    node_a_index: corners[0],
+                    node_b_index: corners[p],
+                    node_c_index: corners[q],
+                    r: 5,
+                })
+            }
+        }
     }
 
+    // Three squares meet at every edge, so there is no outside to cull
     Object {
-        nodes,
-        faces,
+        cull: CullMode::None,
+        ..Object::new(nodes, faces)
     }
 }
 
+/// Triangulated sphere with about `res` nodes
 pub fn create_3_sphere(res: i32, scale: f32) -> Object<Pos3D> {
-    let mut nodes: Vec<Node<Pos3D>> = Vec::new();
-    let faces: Vec<Face> = Vec::new();
+    // Twice as many segments around the equator as from pole to pole
+    let rings = ((res.max(8) as f32 / 2.0).sqrt() as usize).max(2);
 
-    let phi = PI * (3.0 - (5.0_f32).sqrt());
+    parametric_surface(rings * 2, rings, (true, false), Blue, |u, v| {
+        let (theta, phi) = (PI * v, 2.0 * PI * u);
 
-    for i in 0..res {
-        let y = 1.0 - (i as f32 / (res - 1) as f32) * 2.0;
-        let r = (1.0 - y * y).sqrt();
+        Pos3D { x: theta.sin() * phi.cos(), y: theta.cos(), z: theta.sin() * phi.sin() } * scale
+    })
+}
 
-        let theta = phi * i as f32;
+/// Hypersphere with at least about `res` nodes
+#[deprecated(note = "use create_hypersphere, which picks the detail by subdivisions")]
+pub fn create_4_sphere(res: i32, r: f32) -> Object<Pos4D> {
+    // Every subdivision multiplies the nodes by about 8
+    let mut subdivisions = 0;
+    while subdivisions < 3 && 120 * 8_i32.pow(subdivisions) < res {
+        subdivisions += 1;
+    }
 
-        let x = theta.cos() * r;
-        let z = theta.sin() * r;
+    create_hypersphere(subdivisions as usize, r)
+}
 
-        nodes.push(Node {
-            pos: Pos3D { x, y, z } * scale,
-            r: 10,
-            color: Blue,
-        })
+/// Triangulated 3-sphere made by subdividing the 600-cell and pushing the new nodes onto the sphere
+///
+/// Every subdivision splits each tetrahedron into 8, starting from 120 nodes and 600 cells.
+pub fn create_hypersphere(subdivisions: usize, r: f32) -> Object<Pos4D> {
+    let mut positions = hexacosichoron_nodes();
+    let mut cells = hexacosichoron_cells(&positions);
+
+    for _ in 0..subdivisions {
+        // Nodes in the middle of every edge, shared by all cells around the edge
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, positions: &mut Vec<Pos4D>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                let pos = positions[a] + positions[b];
+                positions.push(pos / pos.len());
+                positions.len() - 1
+            })
+        };
+
+        let mut subdivided = Vec::with_capacity(cells.len() * 8);
+        for [a, b, c, d] in cells {
+            let [ab, ac, ad, bc, bd, cd] = [(a, b), (a, c), (a, d), (b, c), (b, d), (c, d)].map(|(p, q)| midpoint(p, q, &mut positions));
+
+            // A tetrahedron at every corner
+            subdivided.extend([[a, ab, ac, ad], [b, ab, bc, bd], [c, ac, bc, cd], [d, ad, bd, cd]]);
+
+            // The octahedron left in the middle is split around its shortest diagonal
+            let diagonals = [(ab, cd, [ac, ad, bd, bc]), (ac, bd, [ab, bc, cd, ad]), (ad, bc, [ab, ac, cd, bd])];
+            let length = |p: usize, q: usize| (positions[p] - positions[q]).len();
+            let (p, q, ring) = diagonals.into_iter().min_by(|x, y| length(x.0, x.1).total_cmp(&length(y.0, y.1))).unwrap();
+
+            for i in 0..4 {
+                subdivided.push([p, q, ring[i], ring[(i + 1) % 4]]);
+            }
+        }
+
+        cells = subdivided;
     }
 
+    let nodes = positions.iter().map(|&pos| Node { pos: pos * r, r: 0, color: Purple }).collect();
+    let cells = cells.into_iter().map(|[a, b, c, d]| Cell { node_a_index: a, node_b_index: b, node_c_index: c, node_d_index: d }).collect();
+
     Object {
-        nodes,
-        faces,
+        // Every node already lies on the unit sphere
+        normals: positions,
+        ..Object::from_cells(nodes, cells, 5)
     }
 }
 
-pub fn create_4_sphere(res: i32, r: f32) -> Object<Pos4D> {
-    let mut nodes: Vec<Node<Pos4D>> = Vec::new();
-    let faces: Vec<Face> = Vec::new();
-
-    let res_per_plane = (res as f32).sqrt() as i32;
+/// The 120 vertices of the 600-cell on the unit 3-sphere
+fn hexacosichoron_nodes() -> Vec<Pos4D> {
+    let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let mut nodes = Vec::with_capacity(120);
+
+    // Permutations of (±1, 0, 0, 0)
+    for axis in 0..4 {
+        for sign in [1.0, -1.0] {
+            let mut c = [0.0; 4];
+            c[axis] = sign;
+            nodes.push(Pos4D { x: c[0], y: c[1], z: c[2], w: c[3] });
+        }
+    }
 
-    // XZ plane
-    for i in 0..res_per_plane {
-        let cos_t: f32 = ((2.0 * PI) / res_per_plane as f32 * i as f32).cos();
-        let sin_t: f32 = ((2.0 * PI) / res_per_plane as f32 * i as f32).sin();
+    // (±1/2, ±1/2, ±1/2, ±1/2)
+    for signs in 0..16 {
+        let c = [0, 1, 2, 3].map(|i| if signs >> i & 1 == 0 { 0.5 } else { -0.5 });
+        nodes.push(Pos4D { x: c[0], y: c[1], z: c[2], w: c[3] });
+    }
 
-        // rotating Z plane
-        for j in 0..res_per_plane {
-            let cos_r: f32 = ((2.0 * PI) / res_per_plane as f32 * j as f32).cos();
-            let sin_r: f32 = ((2.0 * PI) / res_per_plane as f32 * j as f32).sin();
+    // Even permutations of (±φ, ±1, ±1/φ, 0) / 2
+    let values = [phi / 2.0, 0.5, 0.5 / phi, 0.0];
+    for permutation in 0..256 {
+        let permutation: [usize; 4] = [0, 1, 2, 3].map(|i| permutation >> (2 * i) & 3);
+
+        // Only keep permutations with an even number of inversions
+        let pairs = || (0..4).flat_map(|i| (i + 1..4).map(move |j| (i, j)));
+        if pairs().any(|(i, j)| permutation[i] == permutation[j]) { continue; }
+        if pairs().filter(|&(i, j)| permutation[i] > permutation[j]).count() % 2 == 1 { continue; }
+
+        for signs in 0..8 {
+            let mut c = [0.0; 4];
+            for i in 0..4 {
+                let sign = if i < 3 && signs >> i & 1 == 1 { -1.0 } else { 1.0 };
+                c[permutation[i]] = values[i] * sign;
+            }
 
-            // rotating W plane
-            for k in 0..res_per_plane {
-                let cos_s: f32 = ((2.0 * PI) / res_per_plane as f32 * k as f32).cos();
-                let sin_s: f32 = ((2.0 * PI) / res_per_plane as f32 * k as f32).sin();
+            nodes.push(Pos4D { x: c[0], y: c[1], z: c[2], w: c[3] });
+        }
+    }
 
-                let x: f32 = r * sin_t * sin_r * cos_s;
-                let z: f32 = r * sin_t * sin_r * sin_s;
+    nodes
+}
 
-                let y: f32 = r * sin_t * cos_r;
-                let w: f32 = r * cos_t;
+/// The 600 tetrahedra of the 600-cell, found as groups of four nodes that are all one edge length apart
+fn hexacosichoron_cells(nodes: &[Pos4D]) -> Vec<[usize; 4]> {
+    let edge_length = 2.0 / (1.0 + 5.0_f32.sqrt());
+    let adjacent = |a: usize, b: usize| ((nodes[a] - nodes[b]).len() - edge_length).abs() < 1e-3;
 
-                let pos = Pos4D { x, y, z, w };
+    let neighbours: Vec<Vec<usize>> = (0..nodes.len()).map(|a| (a + 1..nodes.len()).filter(|&b| adjacent(a, b)).collect()).collect();
 
-                nodes.push(Node {
-                    pos,
-                    r: 10,
-                    color: Purple,
-                });
+    let mut cells = Vec::with_capacity(600);
+    for a in 0..nodes.len() {
+        for &b in neighbours[a].iter() {
+            for &c in neighbours[b].iter().filter(|c| neighbours[a].contains(c)) {
+                for &d in neighbours[c].iter().filter(|d| neighbours[a].contains(d) && neighbours[b].contains(d)) {
+                    cells.push([a, b, c, d]);
+                }
             }
         }
     }
 
-    Object {
-        nodes,
-        faces,
-    }
+    cells
 }
 
+/// Triangulated torus with `res` segments around both circles, the tube is half as thick as the radius
 pub fn create_torus(res: i32, r: f32) -> Object<Pos3D> {
-    let mut nodes: Vec<Node<Pos3D>> = Vec::new();
-    let faces: Vec<Face> = Vec::new();
-
     let major_r: f32 = r;
     let minor_r: f32 = 0.5 * r;
+    let res = res.max(3) as usize;
 
-    // XZ plane
-    for t in 0..res {
-        let cos_t: f32 = ((2.0 * PI) / res as f32 * t as f32).cos();
-        let sin_t: f32 = ((2.0 * PI) / res as f32 * t as f32).sin();
+    parametric_surface(res, res, (true, true), Purple, |u, v| {
+        let (t, p) = (2.0 * PI * u, 2.0 * PI * v);
 
-        for p in 0..res {
-            let cos_p: f32 = ((2.0 * PI) / res as f32 * p as f32).cos();
-            let sin_p: f32 = ((2.0 * PI) / res as f32 * p as f32).sin();
+        Pos3D {
+            x: (major_r + minor_r * t.cos()) * p.sin(),
+            y: (major_r + minor_r * t.cos()) * p.cos(),
+            z: minor_r * t.sin(),
+        }
+    })
+}
 
-            let x = (major_r + minor_r * cos_t) * sin_p;
-            let y = (major_r + minor_r * cos_t) * cos_p;
-            let z = minor_r * sin_t;
+/// Flat torus on the 3-sphere of radius `r`, the product of two circles of equal size
+pub fn create_clifford_torus(res: usize, r: f32) -> Object<Pos4D> {
+    let r = r / 2.0_f32.sqrt();
 
-            let pos = Pos3D { x, y, z };
+    parametric_surface_4d(res, res, (true, true), Purple, |u, v| {
+        let (a, b) = (2.0 * PI * u, 2.0 * PI * v);
 
-            nodes.push(Node {
-                pos,
-                r: 10,
-                color: Purple,
-            });
-        }
-    }
+        Pos4D { x: r * a.cos(), y: r * a.sin(), z: r * b.cos(), w: r * b.sin() }
+    })
+}
 
-    Object {
-        nodes,
-        faces,
-    }
+/// Surface of the product of two disks of radius `r`, made of two solid tori meeting at a clifford torus
+pub fn create_duocylinder(res: usize, r: f32) -> Object<Pos4D> {
+    let circle = |t: f32| (r * (2.0 * PI * t).cos(), r * (2.0 * PI * t).sin());
+
+    // One disk is filled from the center out to its edge while the other stays on its edge
+    let first = |u: f32, v: f32, s: f32| {
+        let ((x, y), (z, w)) = (circle(u), circle(v));
+        Pos4D { x, y, z: z * s, w: w * s }
+    };
+    let second = |u: f32, v: f32, s: f32| {
+        let ((x, y), (z, w)) = (circle(u), circle(v));
+        Pos4D { x: x * s, y: y * s, z, w }
+    };
+
+    parametric_volume(&[
+        Patch { res: [res, res, res / 4], wrap: [true, true, false], f: &first },
+        Patch { res: [res, res, res / 4], wrap: [true, true, false], f: &second },
+    ], Purple)
+}
+
+/// Surface of the product of a ball of radius `r` and a segment of length `2 * h` along w
+pub fn create_spherinder(res: usize, r: f32, h: f32) -> Object<Pos4D> {
+    let sphere = |u: f32, v: f32| {
+        let (theta, phi) = (PI * v, 2.0 * PI * u);
+        Pos3D { x: theta.sin() * phi.cos(), y: theta.cos(), z: theta.sin() * phi.sin() } * r
+    };
+
+    // The side and the two balls closing the ends
+    let side = |u: f32, v: f32, s: f32| Pos4D { w: h * (2.0 * s - 1.0), ..sphere(u, v).into() };
+    let bottom = |u: f32, v: f32, s: f32| Pos4D { w: -h, ..(sphere(u, v) * s).into() };
+    let top = |u: f32, v: f32, s: f32| Pos4D { w: h, ..(sphere(u, v) * s).into() };
+
+    parametric_volume(&[
+        Patch { res: [res, res / 2, res / 4], wrap: [true, false, false], f: &side },
+        Patch { res: [res, res / 2, res / 4], wrap: [true, false, false], f: &bottom },
+        Patch { res: [res, res / 2, res / 4], wrap: [true, false, false], f: &top },
+    ], Purple)
+}
+
+/// Surface of the product of a disk of radius `r` and a square with sides of `2 * r`
+pub fn create_cubinder(res: usize, r: f32) -> Object<Pos4D> {
+    let circle = |t: f32| (r * (2.0 * PI * t).cos(), r * (2.0 * PI * t).sin());
+    let side = |t: f32| r * (2.0 * t - 1.0);
+
+    // The circle times the filled square, and the disk times each of the four edges of the square
+    let tube = |u: f32, v: f32, s: f32| {
+        let (x, y) = circle(u);
+        Pos4D { x, y, z: side(v), w: side(s) }
+    };
+    let disk = |u: f32, s: f32| {
+        let (x, y) = circle(u);
+        (x * s, y * s)
+    };
+    let caps: [&dyn Fn(f32, f32, f32) -> Pos4D; 4] = [
+        &|u, v, s| { let (x, y) = disk(u, s); Pos4D { x, y, z: -r, w: side(v) } },
+        &|u, v, s| { let (x, y) = disk(u, s); Pos4D { x, y, z: r, w: side(v) } },
+        &|u, v, s| { let (x, y) = disk(u, s); Pos4D { x, y, z: side(v), w: -r } },
+        &|u, v, s| { let (x, y) = disk(u, s); Pos4D { x, y, z: side(v), w: r } },
+    ];
+
+    let quarter = (res / 4).max(1);
+    let mut patches = vec![Patch { res: [res, quarter, quarter], wrap: [true, false, false], f: &tube }];
+    patches.extend(caps.into_iter().map(|f| Patch { res: [res, quarter, quarter], wrap: [true, false, false], f }));
+
+    parametric_volume(&patches, Purple)
+}
+
+/// Points at distance `minor_r` from a clifford torus with circles of radius `major_r_1` and `major_r_2`
+pub fn create_tiger(res: usize, major_r_1: f32, major_r_2: f32, minor_r: f32) -> Object<Pos4D> {
+    let f = |u: f32, v: f32, s: f32| {
+        let (a, b, c) = (2.0 * PI * u, 2.0 * PI * v, 2.0 * PI * s);
+        let (r_1, r_2) = (major_r_1 + minor_r * c.cos(), major_r_2 + minor_r * c.sin());
+
+        Pos4D { x: r_1 * a.cos(), y: r_1 * a.sin(), z: r_2 * b.cos(), w: r_2 * b.sin() }
+    };
+
+    parametric_volume(&[Patch { res: [res, res, res / 2], wrap: [true, true, true], f: &f }], Purple)
+}
+
+/// Torus swept around a circle of radius `major_r`, the torus has radii `middle_r` and `minor_r`
+pub fn create_ditorus(res: usize, major_r: f32, middle_r: f32, minor_r: f32) -> Object<Pos4D> {
+    let f = |u: f32, v: f32, s: f32| {
+        let (a, b, c) = (2.0 * PI * u, 2.0 * PI * v, 2.0 * PI * s);
+        let inner = middle_r + minor_r * c.cos();
+        let outer = major_r + inner * b.cos();
+
+        Pos4D { x: outer * a.cos(), y: outer * a.sin(), z: inner * b.sin(), w: minor_r * c.sin() }
+    };
+
+    parametric_volume(&[Patch { res: [res, res / 2, res / 4], wrap: [true, true, true], f: &f }], Purple)
+}
+
+/// Filled regular polygon in the xy plane with its corners at distance `r` from the center
+pub fn create_polygon(sides: usize, r: f32) -> Object<Pos3D> {
+    let sides = sides.max(3);
+
+    let nodes = (0..sides).map(|i| {
+        let angle = 2.0 * PI * i as f32 / sides as f32;
+
+        Node { pos: Pos3D { x: r * angle.cos(), y: r * angle.sin(), z: 0.0 }, r: 0, color: Purple }
+    }).collect();
+
+    // Fan from the first corner, counterclockwise seen from +z
+    let faces = (1..sides - 1).map(|i| Face { node_a_index: 0, node_b_index: i, node_c_index: i + 1, r: 1 }).collect();
+    let edges = (0..sides).map(|i| Edge { node_a_index: i, node_b_index: (i + 1) % sides, r: 5 }).collect();
+
+    Object { edges, ..Object::new(nodes, faces) }
+}
+
+/// Surface of the product of two regular polygons with `p` and `q` sides
+pub fn create_duoprism(p: usize, q: usize, r: f32) -> Object<Pos4D> {
+    let outline = |sides| Object { faces: Vec::new(), ..create_polygon(sides, r) };
+
+    // The surface of a product is the outline of each polygon times the other one filled,
+    // both products number their nodes the same way
+    let mut a = Object::product(&outline(p), &create_polygon(q, r));
+    let b = Object::product(&create_polygon(p, r), &outline(q));
+    a.cells.extend(b.cells);
+
+    Object::from_cells(a.nodes, a.cells, 5)
+}
+
+/// Graph of z² over the disk of radius `r`
+pub fn create_complex_square(res: usize, r: f32) -> Object<Pos4D> {
+    complex_graph(Domain::Polar { r_min: 0.0, r_max: r }, res, |z| z * z)
+}
+
+/// Graph of exp(z) with the real part between -r and r over one period of the imaginary part
+pub fn create_complex_exp(res: usize, r: f32) -> Object<Pos4D> {
+    complex_graph(Domain::Rect { min: Complex::new(-r, -PI), max: Complex::new(r, PI) }, res, |z| z.exp())
+}
+
+/// Graph of both branches of sqrt(z) over the disk of radius `r`, which join into one surface
+pub fn create_complex_sqrt(res: usize, r: f32) -> Object<Pos4D> {
+    // Halving the angle of the second turn gives the other branch
+    polar_graph(0.0, r, res, 2, |z, angle| Complex::from_polar(z.abs().sqrt(), angle / 2.0))
+}
+
+/// Hopf fibration over three circles of latitude, as thin tubes on the 3-sphere of radius `r`
+pub fn create_hopf_fibration(res: usize, r: f32) -> Object<Pos4D> {
+    let base: Vec<Pos3D> = [-0.6_f32, 0.0, 0.6].into_iter().flat_map(|z| {
+        let ring = (1.0 - z * z).sqrt();
+
+        sample_curve(12, true, move |t| Pos3D { x: ring * (2.0 * PI * t).cos(), y: ring * (2.0 * PI * t).sin(), z })
+    }).collect();
+
+    hopf_fibers(&base, res, r, FiberStyle::Tube { radius: 0.03 * r, sides: 6 })
+}
+
+/// Boundary of the quaternion Julia set of c = -0.2 + 0.8i as a point cloud, sampled `res` times along every axis
+pub fn create_quaternion_julia(res: usize, max_iterations: usize) -> Object<Pos4D> {
+    let (min, max) = (Pos4D { x: -1.5, y: -1.5, z: -1.5, w: -1.5 }, Pos4D { x: 1.5, y: 1.5, z: 1.5, w: 1.5 });
+
+    fractal_points(Fractal::Julia(Quaternion::new(-0.2, 0.8, 0.0, 0.0)), min, max, res, max_iterations)
 }
@@ -0,0 +1,128 @@
+use std::{fmt::Write, fs, path::Path, sync::{Arc, Mutex}};
+
+use crate::{object::Object, pos::{Pos2D, Pos3D}, projection::{Project2D, Project3D, Projection}, render::{Canvas, Color, Vertex}};
+
+/// Shape in the svg document, drawn back to front
+enum Element {
+    Polygon([Pos2D; 3], Color),
+    Line(Pos2D, Pos2D, f32, Color),
+    Circle(Pos2D, f32, Color),
+}
+
+/// Canvas collecting primitives as resolution independent svg elements
+pub struct SvgCanvas {
+    elements: Vec<(f32, Element)>,
+    width: usize,
+    height: usize,
+}
+
+impl SvgCanvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { elements: Vec::new(), width, height }
+    }
+
+    /// Build the svg document, painting the elements from back to front
+    pub fn to_document(&self) -> String {
+        // Larger depths are further away, so paint from large to small
+        let mut order: Vec<usize> = (0..self.elements.len()).collect();
+        order.sort_by(|&a, &b| self.elements[b].0.total_cmp(&self.elements[a].0));
+
+        let mut svg = String::new();
+        let _ = writeln!(svg, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#, self.width, self.height);
+
+        for index in order {
+            let _ = match &self.elements[index].1 {
+                Element::Polygon(points, color) => {
+                    let [r, g, b, a] = color.get_rgba();
+                    writeln!(
+                        svg,
+                        r#"  <polygon points="{:.2},{:.2} {:.2},{:.2} {:.2},{:.2}" fill="rgb({r},{g},{b})" fill-opacity="{:.3}" stroke="rgb({r},{g},{b})" stroke-width="0.5" stroke-linejoin="round"/>"#,
+                        points[0].x, points[0].y, points[1].x, points[1].y, points[2].x, points[2].y, a as f32 / 255.0,
+                    )
+                },
+                Element::Line(start, end, width, color) => {
+                    let [r, g, b, a] = color.get_rgba();
+                    writeln!(
+                        svg,
+                        r#"  <line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="rgb({r},{g},{b})" stroke-opacity="{:.3}" stroke-width="{width:.2}" stroke-linecap="round"/>"#,
+                        start.x, start.y, end.x, end.y, a as f32 / 255.0,
+                    )
+                },
+                Element::Circle(pos, radius, color) => {
+                    let [r, g, b, a] = color.get_rgba();
+                    writeln!(
+                        svg,
+                        r#"  <circle cx="{:.2}" cy="{:.2}" r="{radius:.2}" fill="rgb({r},{g},{b})" fill-opacity="{:.3}"/>"#,
+                        pos.x, pos.y, a as f32 / 255.0,
+                    )
+                },
+            };
+        }
+
+        let _ = writeln!(svg, "</svg>");
+
+        svg
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn draw_point(&mut self, a: Vertex, r: usize) {
+        // Match the size of the squares printed by the raster renderer
+        let radius = r as f32 / 10.0 + 0.5;
+
+        self.elements.push((a.depth, Element::Circle(a.pos, radius, a.color)));
+    }
+
+    fn draw_line(&mut self, a: Vertex, b: Vertex, r: usize) {
+        let width = 2.0 * (r as f32 / 10.0 + 0.5);
+
+        self.elements.push(((a.depth + b.depth) / 2.0, Element::Line(a.pos, b.pos, width, average_color(&[a, b]))));
+    }
+
+    fn draw_triangle(&mut self, a: Vertex, b: Vertex, c: Vertex) {
+        let depth = (a.depth + b.depth + c.depth) / 3.0;
+
+        self.elements.push((depth, Element::Polygon([a.pos, b.pos, c.pos], average_color(&[a, b, c]))));
+    }
+}
+
+/// Svg has no per vertex colors, so use the average
+fn average_color(vertices: &[Vertex]) -> Color {
+    let mut rgba = [0.0; 4];
+    for vertex in vertices {
+        for (c, v) in vertex.color.get_rgba().iter().enumerate() {
+            rgba[c] += *v as f32 / vertices.len() as f32;
+        }
+    }
+
+    Color::RGBA(rgba[0] as u8, rgba[1] as u8, rgba[2] as u8, rgba[3] as u8)
+}
+
+/// Render an object to a standalone svg document
+///
+/// Uses the same projection and face culling as the raster renderer. Faces become filled
+/// polygons with their edges as outline, nodes become circles.
+pub fn to_svg<T>(object: &Object<T>, size: (usize, usize), projection: Projection) -> String
+where
+    T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Into<Pos3D> + std::hash::Hash + Copy + Sync + Send + 'static,
+{
+    let canvas = Arc::new(Mutex::new(SvgCanvas::new(size.0, size.1)));
+
+    object.draw(Arc::clone(&canvas), projection);
+
+    let canvas = canvas.lock().expect("Failed to lock the svg canvas");
+    canvas.to_document()
+}
+
+/// Render an object to an svg file
+pub fn write_svg<T>(object: &Object<T>, size: (usize, usize), projection: Projection, path: &Path) -> Result<(), &'static str>
+where
+    T: Project2D<Output = (Pos2D, f32)> + Project3D<Output = Pos3D> + Into<Pos3D> + std::hash::Hash + Copy + Sync + Send + 'static,
+{
+    fs::write(path, to_svg(object, size, projection)).map_err(|_| "Failed to write svg file")
+}
@@ -0,0 +1,129 @@
+use std::fmt::Write;
+
+use crate::render::Screen;
+
+/// Characters used to draw the image in a terminal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TerminalMode {
+    /// `▀` characters, two square pixels per character with separate colors
+    HalfBlock,
+    /// Braille dots, 2x4 pixels per character sharing a single color
+    Braille,
+}
+
+impl TerminalMode {
+    /// Number of pixels covered by a single character
+    fn cell_size(&self) -> (usize, usize) {
+        match self {
+            TerminalMode::HalfBlock => (1, 2),
+            TerminalMode::Braille => (2, 4),
+        }
+    }
+}
+
+/// Convert the screen to 24-bit ANSI colored text fitting in `columns` x `rows` characters
+///
+/// The image is scaled down keeping its aspect ratio, assuming characters are twice as high as they are wide.
+/// Transparent pixels are left empty so the terminal background shows through.
+pub fn to_ansi(screen: &Screen, columns: usize, rows: usize, mode: TerminalMode) -> String {
+    let (width, height) = screen.size();
+    let (cell_width, cell_height) = mode.cell_size();
+
+    // Size of the downscaled image in terminal pixels, which are square for both modes
+    let scale = (width as f32 / (columns * cell_width) as f32).max(height as f32 / (rows * cell_height) as f32).max(f32::EPSILON);
+    let image_width = ((width as f32 / scale) as usize).max(1);
+    let image_height = ((height as f32 / scale) as usize).max(1);
+
+    let pixels = screen.get_slice();
+
+    // Average all screen pixels covered by a terminal pixel, premultiplied by alpha
+    let sample = |x: usize, y: usize| -> Option<[u8; 3]> {
+        let x0 = (x as f32 * scale) as usize;
+        let y0 = (y as f32 * scale) as usize;
+        let x1 = (((x + 1) as f32 * scale) as usize).clamp(x0 + 1, width);
+        let y1 = (((y + 1) as f32 * scale) as usize).clamp(y0 + 1, height);
+
+        let mut sum = [0.0; 3];
+        let mut coverage = 0.0;
+        let mut count = 0.0;
+
+        for sy in y0..y1.min(height) {
+            for sx in x0..x1.min(width) {
+                let i = (sx + sy * width) * 4;
+                let alpha = pixels[i + 3] as f32 / 255.0;
+
+                for c in 0..3 {
+                    sum[c] += pixels[i + c] as f32 * alpha;
+                }
+                coverage += alpha;
+                count += 1.0;
+            }
+        }
+
+        if coverage == 0.0 || count == 0.0 {
+            return None;
+        }
+
+        Some(sum.map(|v| (v / count).clamp(0.0, 255.0) as u8))
+    };
+
+    let mut text = String::new();
+    let text_rows = image_height.div_ceil(cell_height);
+
+    for row in 0..text_rows {
+        for column in 0..image_width.div_ceil(cell_width) {
+            match mode {
+                TerminalMode::HalfBlock => {
+                    let top = sample(column, row * 2);
+                    let bottom = if row * 2 + 1 < image_height { sample(column, row * 2 + 1) } else { None };
+
+                    let _ = match (top, bottom) {
+                        (None, None) => write!(text, "\x1b[0m "),
+                        (Some([r, g, b]), None) => write!(text, "\x1b[0m\x1b[38;2;{r};{g};{b}m▀"),
+                        (None, Some([r, g, b])) => write!(text, "\x1b[0m\x1b[38;2;{r};{g};{b}m▄"),
+                        (Some([r, g, b]), Some([br, bg, bb])) => write!(text, "\x1b[38;2;{r};{g};{b}m\x1b[48;2;{br};{bg};{bb}m▀"),
+                    };
+                },
+                TerminalMode::Braille => {
+                    // Bit of every dot in the braille pattern, indexed by [y][x]
+                    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+                    let mut pattern = 0;
+                    let mut sum = [0_u32; 3];
+                    let mut lit = 0;
+
+                    for (dy, dots) in DOTS.iter().enumerate() {
+                        for (dx, dot) in dots.iter().enumerate() {
+                            let (x, y) = (column * 2 + dx, row * 4 + dy);
+                            if x >= image_width || y >= image_height { continue; }
+
+                            if let Some(color) = sample(x, y) {
+                                pattern |= dot;
+                                for c in 0..3 {
+                                    sum[c] += color[c] as u32;
+                                }
+                                lit += 1;
+                            }
+                        }
+                    }
+
+                    let _ = if lit == 0 {
+                        write!(text, "\x1b[0m ")
+                    } else {
+                        let [r, g, b] = sum.map(|v| v / lit);
+                        let character = char::from_u32(0x2800 + pattern).unwrap_or(' ');
+                        write!(text, "\x1b[0m\x1b[38;2;{r};{g};{b}m{character}")
+                    };
+                },
+            }
+        }
+
+        // No line break after the last row, so the terminal does not scroll when the text fills it
+        text.push_str("\x1b[0m");
+        if row + 1 < text_rows {
+            text.push_str("\r\n");
+        }
+    }
+
+    text
+}
@@ -23,7 +23,9 @@ impl Transform<Matrix3x3, Pos3D> for Object<Pos3D> {
             *node = node.rotate(rotation_matrix);
         });
 
-        Self { nodes, faces: self.faces.clone() }
+        let normals = self.normals.iter().map(|&normal| rotation_matrix * normal).collect();
+
+        Self { nodes, edges: self.edges.clone(), faces: self.faces.clone(), cells: self.cells.clone(), normals, cull: self.cull }
     }
 
     fn translate(&self, vector: Pos3D) -> Self {
@@ -33,7 +35,7 @@ impl Transform<Matrix3x3, Pos3D> for Object<Pos3D> {
             *node = node.translate(vector);
         });
 
-        Self { nodes, faces: self.faces.clone() }
+        Self { nodes, edges: self.edges.clone(), faces: self.faces.clone(), cells: self.cells.clone(), normals: self.normals.clone(), cull: self.cull }
     }
 
     fn scale(&self, scale: f32) -> Self {
@@ -43,7 +45,10 @@ impl Transform<Matrix3x3, Pos3D> for Object<Pos3D> {
             *node = node.scale(scale)
        });
 
-       Self { nodes, faces: self.faces.clone() }
+       // Mirroring turns the normals around
+       let normals = self.normals.iter().map(|&normal| normal * scale.signum()).collect();
+
+       Self { nodes, edges: self.edges.clone(), faces: self.faces.clone(), cells: self.cells.clone(), normals, cull: self.cull }
     }
 }
 
@@ -69,7 +74,9 @@ impl Transform<Matrix4x4, Pos4D> for Object<Pos4D> {
             *node = node.rotate(rotation_matrix);
         });
 
-        Self { nodes, faces: self.faces.clone() }
+        let normals = self.normals.iter().map(|&normal| rotation_matrix * normal).collect();
+
+        Self { nodes, edges: self.edges.clone(), faces: self.faces.clone(), cells: self.cells.clone(), normals, cull: self.cull }
     }
 
     fn translate(&self, vector: Pos4D) -> Self {
@@ -79,7 +86,7 @@ impl Transform<Matrix4x4, Pos4D> for Object<Pos4D> {
             *node = node.translate(vector);
         });
 
-        Self { nodes, faces: self.faces.clone() }
+        Self { nodes, edges: self.edges.clone(), faces: self.faces.clone(), cells: self.cells.clone(), normals: self.normals.clone(), cull: self.cull }
     }
 
     fn scale(&self, scale: f32) -> Self {
@@ -89,7 +96,10 @@ impl Transform<Matrix4x4, Pos4D> for Object<Pos4D> {
             *node = node.scale(scale)
        });
 
-       Self { nodes, faces: self.faces.clone() }
+       // Mirroring turns the normals around
+       let normals = self.normals.iter().map(|&normal| normal * scale.signum()).collect();
+
+       Self { nodes, edges: self.edges.clone(), faces: self.faces.clone(), cells: self.cells.clone(), normals, cull: self.cull }
     }
 }
 
@@ -0,0 +1,77 @@
+use n_renderer::{
+    animation::{Animation, Interpolation, Keyframe, Playback},
+    pos::{Len, Pos4D, RotationPlane},
+    rotor::Rotor,
+};
+
+/// Keyframes at 0, 2 and 4 seconds with scales 1, 3 and 2, turning in the xy plane
+fn timeline(playback: Playback, interpolation: Interpolation) -> Animation {
+    let keyframes = [(0.0, 1.0), (2.0, 3.0), (4.0, 2.0)].map(|(time, scale)| Keyframe {
+        scale,
+        rotation: Rotor::from_plane(RotationPlane::XY.into(), time * 0.5),
+        translation: Pos4D { x: 0.0, y: 0.0, z: 0.0, w: time },
+        interpolation,
+        ..Keyframe::new(time)
+    });
+
+    // Out of order on purpose, they are sorted by time
+    Animation::new(vec![keyframes[2], keyframes[0], keyframes[1]], playback)
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+#[test]
+fn keyframes_are_hit_exactly() {
+    for interpolation in [Interpolation::Linear, Interpolation::Cubic, Interpolation::Step, Interpolation::EaseInOut] {
+        let animation = timeline(Playback::Once, interpolation);
+
+        for keyframe in animation.keyframes() {
+            let sample = animation.sample(keyframe.time);
+
+            assert_eq!(sample.scale, keyframe.scale);
+            assert_eq!(sample.translation, keyframe.translation);
+
+            let x = Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 };
+            assert!((sample.rotation * x - keyframe.rotation * x).len() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn samples_between_keyframes_are_interpolated() {
+    let linear = timeline(Playback::Once, Interpolation::Linear);
+    assert!(close(linear.sample(1.0).scale, 2.0));
+    assert!(close(linear.sample(3.0).scale, 2.5));
+    assert!(close(linear.sample(1.5).translation.w, 1.5));
+
+    // Halfway between two rotations is half the angle
+    let x = Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 };
+    let halfway = Rotor::from_plane(RotationPlane::XY.into(), 0.5);
+    assert!((linear.sample(1.0).rotation * x - halfway * x).len() < 1e-5);
+
+    // Step holds the value of the previous keyframe, easing stays between the two keyframes
+    assert_eq!(timeline(Playback::Once, Interpolation::Step).sample(1.9).scale, 1.0);
+    let eased = timeline(Playback::Once, Interpolation::EaseIn).sample(1.0).scale;
+    assert!(eased > 1.0 && eased < 2.0);
+}
+
+#[test]
+fn playback_modes() {
+    let scale = |playback, time| timeline(playback, Interpolation::Linear).sample(time).scale;
+
+    // Once holds the ends
+    assert!(close(scale(Playback::Once, -1.0), 1.0));
+    assert!(close(scale(Playback::Once, 10.0), 2.0));
+
+    // Loop starts over after 4 seconds
+    assert!(close(scale(Playback::Loop, 5.0), scale(Playback::Loop, 1.0)));
+    assert!(close(scale(Playback::Loop, 9.0), 2.0));
+
+    // PingPong runs backwards after 4 seconds and forwards again after 8
+    assert!(close(scale(Playback::PingPong, 5.0), scale(Playback::PingPong, 3.0)));
+    assert!(close(scale(Playback::PingPong, 7.0), 2.0));
+    assert!(close(scale(Playback::PingPong, 9.0), 2.0));
+    assert!(close(scale(Playback::PingPong, 8.0), 1.0));
+}
@@ -0,0 +1,39 @@
+use n_renderer::render::Color;
+
+/// Every component within 1 of the expected value, for rounding
+fn close(color: Color, expected: [u8; 4]) -> bool {
+    color.get_rgba().iter().zip(expected).all(|(&a, b)| a.abs_diff(b) <= 1)
+}
+
+#[test]
+fn hsv_primaries_and_grays() {
+    assert_eq!(Color::HSV(0, 255, 255).get_rgba(), [255, 0, 0, 255]);
+    assert_eq!(Color::HSV(120, 255, 255).get_rgba(), [0, 255, 0, 255]);
+    assert_eq!(Color::HSV(240, 255, 255).get_rgba(), [0, 0, 255, 255]);
+
+    // The hue wraps around
+    assert_eq!(Color::HSV(360, 255, 255).get_rgba(), Color::HSV(0, 255, 255).get_rgba());
+
+    // Without saturation the hue does not matter
+    for h in [0, 90, 200, 330] {
+        assert_eq!(Color::HSV(h, 0, 0).get_rgba(), [0, 0, 0, 255]);
+        assert_eq!(Color::HSV(h, 0, 128).get_rgba(), [128, 128, 128, 255]);
+        assert_eq!(Color::HSV(h, 0, 255).get_rgba(), [255, 255, 255, 255]);
+    }
+}
+
+#[test]
+fn hsl_midpoints() {
+    // Full saturation at half lightness is the pure hue
+    assert!(close(Color::HSL(0, 255, 128), [255, 0, 0, 255]));
+    assert!(close(Color::HSL(120, 255, 128), [0, 255, 0, 255]));
+    assert!(close(Color::HSL(240, 255, 128), [0, 0, 255, 255]));
+
+    // Halfway to black and halfway to white
+    assert!(close(Color::HSL(0, 255, 64), [128, 0, 0, 255]));
+    assert!(close(Color::HSL(0, 255, 192), [255, 128, 128, 255]));
+
+    for h in [0, 120, 240] {
+        assert_eq!(Color::HSL(h, 0, 128).get_rgba(), [128, 128, 128, 255]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex};
+
+use n_renderer::{
+    hull::convex_hull_3d,
+    implicit::marching_tetrahedra,
+    object::{CullMode, Object},
+    pos::{Len, Pos3D},
+    projection::{Projection, ProjectionType},
+    render::{Color, Primitive, Screen},
+    shapes::{create_3_cube, create_3_sphere, create_4_cube, create_torus},
+};
+
+/// Number of pixels drawn for the object
+fn coverage(object: &Object<Pos3D>, r#type: ProjectionType) -> usize {
+    let screen = Arc::new(Mutex::new(Screen::new(64, 64)));
+    object.draw(Arc::clone(&screen), Projection::new(r#type, 0.3));
+
+    let screen = screen.lock().unwrap();
+    screen.get_slice().chunks(4).filter(|pixel| pixel[3] > 0).count()
+}
+
+/// Number of triangles the faces of the object turn into
+fn triangles(object: &Object<Pos3D>, r#type: ProjectionType) -> usize {
+    object.faces.iter()
+        .flat_map(|face| face.draw_culled(&object.nodes, (64, 64), Projection::new(r#type, 0.3), object.cull))
+        .filter(|primitive| matches!(primitive, Primitive::Triangle(..)))
+        .count()
+}
+
+#[test]
+fn generated_shapes_have_consistent_winding() {
+    let (min, max) = (Pos3D { x: -1.3, y: -1.3, z: -1.3 }, Pos3D { x: 1.3, y: 1.3, z: 1.3 });
+    let corners = create_3_cube(1.0).nodes.iter().map(|node| node.pos).collect();
+
+    let shapes = [
+        create_3_cube(1.0),
+        create_3_sphere(200, 1.0),
+        create_torus(16, 1.0),
+        marching_tetrahedra(|pos| pos.len() - 1.0, min, max, 8, Color::White),
+        convex_hull_3d(corners).unwrap(),
+    ];
+
+    for shape in shapes.iter() {
+        assert!(shape.inconsistent_winding().is_empty());
+    }
+}
+
+#[test]
+fn cull_modes_keep_the_expected_triangles() {
+    let cube = create_3_cube(0.5);
+
+    // The perspective camera looks at one side of the cube and the stereographic one at a corner. The collapse
+    // projection looks straight along the side faces, which face neither way and are kept by both modes.
+    for (r#type, back, front) in [(ProjectionType::Perspective, 2, 10), (ProjectionType::Stereographic, 6, 6), (ProjectionType::Collapse, 10, 10)] {
+        assert_eq!(triangles(&Object { cull: CullMode::Back, ..cube.clone() }, r#type), back);
+        assert_eq!(triangles(&Object { cull: CullMode::Front, ..cube.clone() }, r#type), front);
+        assert_eq!(triangles(&Object { cull: CullMode::None, ..cube.clone() }, r#type), 12);
+    }
+}
+
+#[test]
+fn cull_modes_split_the_faces_of_a_closed_surface() {
+    let cube = create_3_cube(0.5);
+
+    // Both halves show up, and together they are what is drawn without culling
+    for r#type in [ProjectionType::Perspective, ProjectionType::Stereographic, ProjectionType::Collapse] {
+        let back = coverage(&Object { cull: CullMode::Back, ..cube.clone() }, r#type);
+        let front = coverage(&Object { cull: CullMode::Front, ..cube.clone() }, r#type);
+        let none = coverage(&Object { cull: CullMode::None, ..cube.clone() }, r#type);
+
+        assert!(back > 0 && front > 0);
+        assert!(none >= back.max(front));
+    }
+}
+
+#[test]
+fn four_cube_is_drawn_from_every_side() {
+    let cube = create_4_cube(0.5);
+
+    assert_eq!(cube.cull, CullMode::None);
+    assert_eq!(cube.faces.len(), 48);
+}
@@ -0,0 +1,180 @@
+use std::sync::{Arc, Mutex};
+
+use n_renderer::{
+    object::{Face, Node, Object},
+    pos::Pos3D,
+    projection::{Projection, ProjectionType},
+    render::{Color, DepthCompare, Screen},
+};
+
+const SIZE: usize = 64;
+
+/// Projection with a scale that makes a unit square cover the center of the screen
+fn projection(r#type: ProjectionType) -> Projection {
+    match r#type {
+        ProjectionType::Perspective => Projection::new(r#type, 1.0),
+        ProjectionType::Stereographic => Projection::new(r#type, 0.5),
+        ProjectionType::Collapse => Projection::new(r#type, 50.0),
+    }
+}
+
+/// Direction into the screen and two directions spanning the screen for every projection
+fn view_axes(r#type: ProjectionType) -> [Pos3D; 3] {
+    let pos = |x: f32, y: f32, z: f32| Pos3D { x, y, z };
+
+    match r#type {
+        ProjectionType::Perspective => [pos(1.0, 0.0, 0.0), pos(0.0, 1.0, 0.0), pos(0.0, 0.0, 1.0)],
+        ProjectionType::Stereographic => {
+            let (a, b) = (3.0_f32.sqrt(), 2.0_f32.sqrt());
+            [pos(-1.0 / a, 1.0 / a, -1.0 / a), pos(1.0 / b, 1.0 / b, 0.0), pos(0.5 / b, -0.5 / b, -1.0 / b)]
+        },
+        ProjectionType::Collapse => [pos(0.0, 0.0, 1.0), pos(1.0, 0.0, 0.0), pos(0.0, 1.0, 0.0)],
+    }
+}
+
+/// Square facing the camera at a distance along the view direction, with faces in both windings so culling keeps one
+fn square(r#type: ProjectionType, distance: f32, color: Color) -> Object<Pos3D> {
+    let [view, u, v] = view_axes(r#type);
+
+    let nodes = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .map(|(a, b)| Node { pos: view * distance + u * a + v * b, color, r: 0 })
+        .to_vec();
+
+    let face = |a, b, c| Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 };
+    let faces = vec![face(0, 1, 2), face(0, 2, 3), face(0, 2, 1), face(0, 3, 2)];
+
+    Object::new(nodes, faces)
+}
+
+/// Merge the objects, keeping their order
+fn scene(objects: &[Object<Pos3D>]) -> Object<Pos3D> {
+    let mut scene = Object::new(Vec::new(), Vec::new());
+
+    for object in objects {
+        let offset = scene.nodes.len();
+
+        scene.nodes.extend_from_slice(&object.nodes);
+        scene.faces.extend(object.faces.iter().map(|face| Face {
+            node_a_index: face.node_a_index + offset,
+            node_b_index: face.node_b_index + offset,
+            node_c_index: face.node_c_index + offset,
+            r: face.r,
+        }));
+    }
+
+    scene
+}
+
+/// Draw the object and return the color of the center pixel
+fn center_color(object: &Object<Pos3D>, projection: Projection, screen: Screen) -> [u8; 4] {
+    let screen = Arc::new(Mutex::new(screen));
+    object.draw(Arc::clone(&screen), projection);
+
+    let screen = screen.lock().unwrap();
+    let i = (SIZE / 2 + SIZE / 2 * SIZE) * 4;
+    let pixel = screen.get_slice();
+
+    [pixel[i], pixel[i + 1], pixel[i + 2], pixel[i + 3]]
+}
+
+const TYPES: [ProjectionType; 3] = [ProjectionType::Perspective, ProjectionType::Stereographic, ProjectionType::Collapse];
+
+#[test]
+fn occluded_faces_are_hidden() {
+    for r#type in TYPES {
+        let near = square(r#type, -0.5, Color::Red);
+        let far = square(r#type, 0.5, Color::Blue);
+
+        // The near square has to win no matter the draw order
+        for objects in [[near.clone(), far.clone()], [far.clone(), near.clone()]] {
+            let [r, _, b, a] = center_color(&scene(&objects), projection(r#type), Screen::new(SIZE, SIZE));
+
+            assert!(a > 0, "nothing drawn");
+            assert!(r > 0 && b == 0, "far square visible: {:?}", [r, b]);
+        }
+    }
+}
+
+#[test]
+fn depth_is_normalized_and_increases_away_from_the_camera() {
+    for r#type in TYPES {
+        let projection = projection(r#type);
+        let [view, ..] = view_axes(r#type);
+
+        let near = projection.depth(view * -1.0);
+        let origin = projection.depth(Pos3D { x: 0.0, y: 0.0, z: 0.0 });
+        let far = projection.depth(view * 1.0);
+
+        assert!(near < origin && origin < far);
+        assert!((0.0..=1.0).contains(&near) && (0.0..=1.0).contains(&far));
+        assert!((origin - 0.5).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn reversed_depth_hides_the_same_faces() {
+    let r#type = ProjectionType::Stereographic;
+    let projection = Projection { near: 10.0, far: -10.0, ..projection(r#type) };
+
+    let mut screen = Screen::new(SIZE, SIZE);
+    screen.depth_compare = DepthCompare::Greater;
+
+    let objects = [square(r#type, -0.5, Color::Red), square(r#type, 0.5, Color::Blue)];
+    let [r, _, b, _] = center_color(&scene(&objects), projection, screen);
+
+    assert!(r > 0 && b == 0);
+}
+
+#[test]
+fn always_and_write_mask_let_later_faces_through() {
+    let r#type = ProjectionType::Collapse;
+    let objects = [square(r#type, -0.5, Color::Red), square(r#type, 0.5, Color::Blue)];
+
+    // Always keeps the last face drawn
+    let mut screen = Screen::new(SIZE, SIZE);
+    screen.depth_compare = DepthCompare::Always;
+
+    let [r, _, b, _] = center_color(&scene(&objects), projection(r#type), screen);
+    assert!(b > 0 && r == 0);
+
+    // Without depth writes nothing occludes anything
+    let mut screen = Screen::new(SIZE, SIZE);
+    screen.depth_write = false;
+
+    let [r, _, b, _] = center_color(&scene(&objects), projection(r#type), screen);
+    assert!(b > 0 && r == 0);
+}
+
+#[test]
+fn every_projection_is_right_handed() {
+    for r#type in TYPES {
+        let projection = projection(r#type);
+        let origin = projection.project(Pos3D { x: 0.0, y: 0.0, z: 0.0 }, (SIZE, SIZE));
+
+        // Steps to the right on the screen, down the screen and away from the camera for a step along every axis
+        let steps = [Pos3D { x: 0.5, y: 0.0, z: 0.0 }, Pos3D { x: 0.0, y: 0.5, z: 0.0 }, Pos3D { x: 0.0, y: 0.0, z: 0.5 }].map(|step| {
+            let (pos, depth) = projection.project(step, (SIZE, SIZE));
+
+            [pos.x - origin.0.x, pos.y - origin.0.y, depth - origin.1]
+        });
+
+        let [a, b, c] = steps;
+        let determinant = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0]) + a[2] * (b[0] * c[1] - b[1] * c[0]);
+        assert!(determinant > 0.0);
+    }
+}
+
+#[test]
+fn depth_range_follows_the_scale() {
+    // Scales that fit a scene 50 units across on the screen, which was clipped by a fixed range
+    for (r#type, scale) in [(ProjectionType::Perspective, 25.0), (ProjectionType::Stereographic, 0.01), (ProjectionType::Collapse, 1.0)] {
+        let projection = Projection::new(r#type, scale);
+        let [view, ..] = view_axes(r#type);
+
+        assert!(projection.depth(view * -25.0) > 0.0 && projection.depth(view * 25.0) < 1.0);
+    }
+
+    // Or pick the range by hand
+    let projection = Projection::with_depth_range(ProjectionType::Collapse, 1.0, 0.0, 2.0);
+    assert_eq!(projection.depth(Pos3D { x: 0.0, y: 0.0, z: 1.0 }), 0.5);
+}
@@ -0,0 +1,61 @@
+use n_renderer::{
+    colormap::Gradient,
+    depth_cue::{DepthCue, Fog},
+    object::{Node, Object},
+    pos::Pos4D,
+    render::Color,
+};
+
+/// White nodes along the w axis
+fn line(ws: &[f32]) -> Object<Pos4D> {
+    let nodes = ws.iter().map(|&w| Node { pos: Pos4D { x: 0.0, y: 0.0, z: 0.0, w }, color: Color::White, r: 1 }).collect();
+
+    Object::new(nodes, Vec::new())
+}
+
+fn colors(object: &Object<Pos4D>) -> Vec<[u8; 4]> {
+    object.nodes.iter().map(|node| node.color.get_rgba()).collect()
+}
+
+#[test]
+fn gradient_spans_the_w_range() {
+    let cue = DepthCue::new(Gradient::evenly_spaced(&[Color::Red, Color::Blue]));
+    let cued = cue.apply(&line(&[-1.0, 0.0, 1.0]));
+
+    // The ends of the object get the ends of the gradient
+    assert_eq!(cued.nodes[0].color, Color::Red);
+    assert_eq!(cued.nodes[1].color, Color::Red.mix(Color::Blue, 0.5));
+    assert_eq!(cued.nodes[2].color, Color::Blue);
+
+    // A fixed range places the object inside it, and clamps what lies outside
+    let fixed = DepthCue { range: Some((-2.0, 2.0)), ..cue.clone() }.apply(&line(&[-1.0, 3.0]));
+    assert_eq!(fixed.nodes[0].color, Color::Red.mix(Color::Blue, 0.25));
+    assert_eq!(fixed.nodes[1].color, Color::Blue);
+
+    // Along another axis w does not matter
+    let along_x = DepthCue { axis: Pos4D { x: 2.0, y: 0.0, z: 0.0, w: 0.0 }, ..cue }.apply(&line(&[-1.0, 1.0]));
+    assert_eq!(colors(&along_x)[0], colors(&along_x)[1]);
+}
+
+#[test]
+fn fog_fades_between_start_and_end() {
+    let cue = DepthCue { gradient: None, fog: Some(Fog::new(0.0, 1.0)), ..DepthCue::new(Gradient::new(Vec::new())) };
+    let fogged = cue.apply(&line(&[-0.5, 0.0, 0.5, 1.0, 2.0]));
+
+    // Untouched up to the start, halfway faded in the middle and gone from the end on
+    assert_eq!(colors(&fogged), [
+        [255, 255, 255, 255],
+        [255, 255, 255, 255],
+        [128, 128, 128, 128],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+    ]);
+
+    // Fog works on top of the gradient, towards its own color
+    let cue = DepthCue {
+        fog: Some(Fog { color: Color::White, ..Fog::new(0.0, 1.0) }),
+        ..DepthCue::new(Gradient::evenly_spaced(&[Color::Red, Color::Blue]))
+    };
+    let fogged = cue.apply(&line(&[-1.0, 1.0]));
+    assert_eq!(colors(&fogged), [Color::Red.get_rgba(), [255, 255, 255, 255]]);
+}
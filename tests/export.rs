@@ -0,0 +1,114 @@
+use std::{fs::{self, File}, path::PathBuf};
+
+use n_renderer::{
+    animation::{Animation, Keyframe, Playback},
+    export::{render_animation, write_depth_pfm, write_depth_png, write_gif, write_png_sequence},
+    pos::RotationPlane,
+    projection::ProjectionType,
+    render::{Color, DepthCompare, Screen},
+    rotor::Rotor,
+    shapes::create_4_cube,
+};
+
+/// Empty directory for the files of one test
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("n_renderer_{}_{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+fn spin() -> Animation {
+    Animation::new(vec![
+        Keyframe { projection_scale: 0.5, ..Keyframe::new(0.0) },
+        Keyframe { projection_scale: 0.5, rotation: Rotor::from_plane(RotationPlane::XW.into(), 1.0), ..Keyframe::new(1.0) },
+    ], Playback::Loop)
+}
+
+#[test]
+fn rendering_is_deterministic() {
+    let render = || render_animation(&create_4_cube(1.0), &spin(), ProjectionType::Stereographic, (48, 32), 2, 3, 0.25);
+    let (a, b) = (render(), render());
+
+    assert_eq!(a.len(), 3);
+    for (a, b) in a.iter().zip(b.iter()) {
+        assert_eq!(a.size(), (48, 32));
+        assert_eq!(a.get_slice(), b.get_slice());
+        assert!(a.get_slice().chunks(4).any(|pixel| pixel[3] > 0));
+    }
+
+    // The encoded files are the same byte for byte
+    let dir = output_dir("deterministic");
+    write_gif(&a, &dir.join("a.gif"), 0.25).unwrap();
+    write_gif(&b, &dir.join("b.gif"), 0.25).unwrap();
+    assert_eq!(fs::read(dir.join("a.gif")).unwrap(), fs::read(dir.join("b.gif")).unwrap());
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn png_sequence_writes_a_file_per_frame() {
+    let frames = render_animation(&create_4_cube(1.0), &spin(), ProjectionType::Stereographic, (16, 16), 1, 5, 0.1);
+    let dir = output_dir("sequence");
+
+    write_png_sequence(&frames, &dir).unwrap();
+
+    let mut names: Vec<String> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+    names.sort();
+    assert_eq!(names, ["frame_0000.png", "frame_0001.png", "frame_0002.png", "frame_0003.png", "frame_0004.png"]);
+
+    fs::remove_dir_all(dir).ok();
+}
+
+/// Screen of 3 x 2 pixels with a near pixel in the top row and a far one in the bottom row, the rest empty
+fn depth_screen() -> Screen {
+    let mut screen = Screen::new(3, 2);
+    screen.write(1, 0, Color::White, 0.25).unwrap();
+    screen.write(2, 1, Color::White, 0.75).unwrap();
+
+    screen
+}
+
+#[test]
+fn depth_pfm_round_trip() {
+    let dir = output_dir("pfm");
+    let path = dir.join("depth.pfm");
+
+    let mut screen = depth_screen();
+    write_depth_pfm(&screen, &path).unwrap();
+
+    let header = b"Pf\n3 2\n-1.0\n";
+    let data = fs::read(&path).unwrap();
+    assert_eq!(&data[..header.len()], header);
+    assert_eq!(data.len(), header.len() + 3 * 2 * 4);
+
+    // Rows are stored from the bottom up, empty pixels get the far depth
+    let depths: Vec<f32> = data[header.len()..].chunks(4).map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap())).collect();
+    assert_eq!(depths, [1.0, 1.0, 0.75, 1.0, 0.25, 1.0]);
+
+    // With reversed depth the far end is 0
+    screen.depth_compare = DepthCompare::Greater;
+    write_depth_pfm(&screen, &path).unwrap();
+    let data = fs::read(&path).unwrap();
+    assert_eq!(f32::from_le_bytes(data[header.len()..header.len() + 4].try_into().unwrap()), 0.0);
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn depth_png_is_white_near_and_black_far() {
+    let dir = output_dir("depth_png");
+    let path = dir.join("depth.png");
+    write_depth_png(&depth_screen(), &path).unwrap();
+
+    let mut reader = png::Decoder::new(File::open(&path).unwrap()).read_info().unwrap();
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).unwrap();
+    assert_eq!((info.width, info.height, info.color_type), (3, 2, png::ColorType::GrayscaleAlpha));
+
+    // Gray and alpha for every pixel, empty ones are as dark as the far end and transparent
+    assert_eq!(&data[..info.buffer_size()], [0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 255]);
+
+    fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,56 @@
+use n_renderer::{
+    colormap::Colormap,
+    fractal::{fractal_mesh, fractal_points, Fractal},
+    pos::{Len, Pos4D},
+    quaternion::Quaternion,
+    render::Color,
+};
+
+fn pos(x: f32, y: f32, z: f32, w: f32) -> Pos4D {
+    Pos4D { x, y, z, w }
+}
+
+#[test]
+fn escape_times() {
+    // The origin and -1 are in the Mandelbrot set, 1 goes to 1, 2 and escapes at 5
+    assert_eq!(Fractal::Mandelbrot.escape_time(pos(0.0, 0.0, 0.0, 0.0), 50), None);
+    assert_eq!(Fractal::Mandelbrot.escape_time(pos(-1.0, 0.0, 0.0, 0.0), 50), None);
+    assert_eq!(Fractal::Mandelbrot.escape_time(pos(1.0, 0.0, 0.0, 0.0), 50), Some(2));
+
+    // Without c the Julia set is the unit 3-sphere, points escape once squaring takes them past 4
+    let julia = Fractal::Julia(Quaternion::new(0.0, 0.0, 0.0, 0.0));
+    assert_eq!(julia.escape_time(pos(0.5, 0.5, 0.5, 0.0), 50), None);
+    assert_eq!(julia.escape_time(pos(0.0, 1.5, 0.0, 0.0), 50), Some(1));
+}
+
+#[test]
+fn point_cloud_hugs_the_boundary() {
+    let julia = Fractal::Julia(Quaternion::new(0.0, 0.0, 0.0, 0.0));
+    let (min, max) = (pos(-1.5, -1.5, -1.5, -1.5), pos(1.5, 1.5, 1.5, 1.5));
+    let points = fractal_points(julia, min, max, 12, 20);
+
+    // Only bounded points next to escaping ones are kept, so they all sit within a step of 0.25 of the unit sphere
+    assert!(!points.nodes.is_empty());
+    assert!(points.nodes.iter().all(|node| node.pos.len() <= 1.0 && node.pos.len() > 0.75));
+    assert!(points.nodes.iter().all(|node| node.r > 0));
+
+    let mesh = fractal_mesh(julia, min, max, 6, 20, Color::White);
+    assert_eq!(mesh.faces.len() * 2, mesh.cells.len() * 4);
+}
+
+#[test]
+fn points_are_colored_by_their_slowest_neighbour() {
+    let julia = Fractal::Julia(Quaternion::new(0.0, 0.0, 0.0, 0.0));
+
+    // A grid of 3 steps along every axis with only its center bounded, the neighbours on the low side escape sooner
+    let (min, max) = (pos(-1.5, -1.5, -1.5, -1.5), pos(1.2, 1.2, 1.2, 1.2));
+    let points = fractal_points(julia, min, max, 2, 20);
+    assert_eq!(points.nodes.len(), 1);
+
+    let center = points.nodes[0].pos;
+    let low = julia.escape_time(Pos4D { x: -1.5, ..center }, 20).unwrap();
+    let high = julia.escape_time(Pos4D { x: 1.2, ..center }, 20).unwrap();
+    assert!(low < high);
+
+    assert_eq!(points.nodes[0].color, Colormap::Magma.sample((high + 1) as f32 / 20.0));
+}
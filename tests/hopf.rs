@@ -0,0 +1,49 @@
+use n_renderer::{
+    complex::Complex,
+    hopf::{hopf_fibers, sample_curve, FiberStyle},
+    pos::{Len, Pos3D, Pos4D},
+    shapes::create_hopf_fibration,
+};
+
+/// Base point of the fiber through a point of the unit 3-sphere
+fn hopf_map(p: Pos4D) -> Pos3D {
+    let (z1, z2) = (Complex::new(p.x, p.y), Complex::new(p.z, p.w));
+    let xy = z1 * z2.conj() * 2.0;
+
+    Pos3D { x: xy.re, y: xy.im, z: z1.abs().powi(2) - z2.abs().powi(2) }
+}
+
+#[test]
+fn fibers_map_back_to_their_base_points() {
+    // Both poles, the equator and a point in between
+    let base = vec![
+        Pos3D { x: 0.0, y: 0.0, z: 1.0 },
+        Pos3D { x: 0.0, y: 0.0, z: -1.0 },
+        Pos3D { x: 0.0, y: 1.0, z: 0.0 },
+        Pos3D { x: 1.0, y: -2.0, z: -2.0 },
+    ];
+    let fibers = hopf_fibers(&base, 16, 1.5, FiberStyle::Loop);
+
+    assert_eq!((fibers.nodes.len(), fibers.edges.len()), (64, 64));
+
+    for (i, node) in fibers.nodes.iter().enumerate() {
+        assert!((node.pos.len() - 1.5).abs() < 1e-5);
+
+        let point = base[i / 16] / base[i / 16].len();
+        assert!((hopf_map(node.pos / 1.5) - point).len() < 1e-5);
+    }
+}
+
+#[test]
+fn tubes_stay_on_the_3_sphere() {
+    let base = sample_curve(5, true, |t| Pos3D { x: (6.0 * t).cos(), y: (6.0 * t).sin(), z: 0.3 });
+    assert_eq!(base.len(), 5);
+
+    let tubes = hopf_fibers(&base, 12, 1.0, FiberStyle::Tube { radius: 0.05, sides: 4 });
+    assert_eq!(tubes.faces.len(), 5 * 12 * 4 * 2);
+    assert!(tubes.nodes.iter().all(|node| (node.pos.len() - 1.0).abs() < 1e-5));
+
+    // Every tube is a closed torus of its own
+    assert!(tubes.validate().is_empty());
+    assert!(create_hopf_fibration(16, 1.5).validate().is_empty());
+}
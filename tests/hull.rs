@@ -0,0 +1,67 @@
+use n_renderer::{
+    hull::{convex_hull_3d, convex_hull_4d},
+    pos::{Pos3D, Pos4D},
+    shapes::{create_3_cube, create_4_cube, create_hypersphere},
+};
+
+fn counts(object: &n_renderer::object::Object<Pos4D>) -> (usize, usize, usize, usize) {
+    (object.nodes.len(), object.edges.len(), object.faces.len(), object.cells.len())
+}
+
+#[test]
+fn cube_hull_ignores_inner_points() {
+    let mut points: Vec<Pos3D> = create_3_cube(1.0).nodes.iter().map(|node| node.pos).collect();
+    points.insert(3, Pos3D { x: 0.1, y: -0.2, z: 0.3 });
+    points.push(Pos3D { x: 0.0, y: 0.0, z: 0.0 });
+
+    let hull = convex_hull_3d(points).unwrap();
+
+    // Squares are split into two triangles but their diagonals are no edges
+    assert_eq!((hull.nodes.len(), hull.edges.len(), hull.faces.len()), (8, 12, 12));
+    assert!(hull.edges.iter().all(|edge| edge.r > 0));
+    assert!(hull.validate().is_empty(), "{:?}", hull.validate());
+}
+
+#[test]
+fn regular_polytopes() {
+    let tesseract = convex_hull_4d(create_4_cube(1.0).nodes.iter().map(|node| node.pos).collect()).unwrap();
+    let (nodes, edges, faces, cells) = counts(&tesseract);
+
+    // How the cubes are split into tetrahedra depends on the order of the points, not how many squares show
+    assert_eq!((nodes, edges, faces), (16, 32, 48));
+    assert!(cells >= 8 * 5);
+
+    // The octahedra of the 24-cell are flat, with every vertex on the same sphere
+    let mut points = Vec::new();
+    for i in 0..4 {
+        for j in i + 1..4 {
+            for (a, b) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+                let mut pos = [0.0; 4];
+                pos[i] = a;
+                pos[j] = b;
+
+                points.push(Pos4D { x: pos[0], y: pos[1], z: pos[2], w: pos[3] });
+            }
+        }
+    }
+
+    let icositetrachoron = convex_hull_4d(points.clone()).unwrap();
+    let (nodes, edges, faces, cells) = counts(&icositetrachoron);
+    assert_eq!((nodes, edges, faces), (24, 96, 96));
+    assert!(cells >= 24 * 4);
+
+    // Rounding errors below the tolerance keep the octahedra flat
+    let jittered = points.iter().enumerate().map(|(i, &pos)| pos * (1.0 + 1e-6 * (i % 3) as f32)).collect();
+    assert_eq!(counts(&convex_hull_4d(jittered).unwrap()), (nodes, edges, faces, cells));
+
+    let hexacosichoron = convex_hull_4d(create_hypersphere(0, 1.0).nodes.iter().map(|node| node.pos).collect()).unwrap();
+    assert_eq!(counts(&hexacosichoron), (120, 720, 1200, 600));
+}
+
+#[test]
+fn flat_points_have_no_hull() {
+    let points = (0..10).map(|i| Pos4D { x: i as f32, y: (i * i) as f32, z: 1.0, w: -(i as f32) }).collect();
+
+    assert!(convex_hull_4d(points).is_err());
+    assert!(convex_hull_3d(Vec::new()).is_err());
+}
@@ -0,0 +1,83 @@
+use n_renderer::{
+    implicit::{marching_pentachora, marching_tetrahedra},
+    object::Object,
+    pos::{Len, Pos3D, Pos4D},
+    render::Color,
+};
+
+fn euler<T>(object: &Object<T>) -> i64 {
+    object.nodes.len() as i64 - object.edges.len() as i64 + object.faces.len() as i64 - object.cells.len() as i64
+}
+
+#[test]
+fn marching_tetrahedra_sphere_and_torus() {
+    let (min, max) = (Pos3D { x: -1.3, y: -1.3, z: -1.3 }, Pos3D { x: 1.3, y: 1.3, z: 1.3 });
+    let sphere = marching_tetrahedra(|pos| pos.len() - 1.0, min, max, 12, Color::White);
+
+    assert!(sphere.validate().is_empty(), "{:?}", sphere.validate());
+    assert_eq!(sphere.nodes.len() as i64 - (sphere.faces.len() * 3 / 2) as i64 + sphere.faces.len() as i64, 2);
+    assert!(sphere.nodes.iter().all(|node| (node.pos.len() - 1.0).abs() < 0.05));
+
+    // Faces point out of the inside, so the normals point away from the center
+    assert!(sphere.nodes.iter().zip(sphere.normals.iter()).all(|(node, &normal)| (node.pos >> normal) > 0.0));
+
+    let torus = marching_tetrahedra(|pos| {
+        let ring = (pos.x * pos.x + pos.y * pos.y).sqrt() - 0.9;
+
+        ring * ring + pos.z * pos.z - 0.3 * 0.3
+    }, min, max, 20, Color::White);
+
+    assert!(torus.validate().is_empty(), "{:?}", torus.validate());
+    assert_eq!(torus.nodes.len() as i64 - (torus.faces.len() * 3 / 2) as i64 + torus.faces.len() as i64, 0);
+}
+
+#[test]
+fn marching_pentachora_hypersurfaces_are_closed() {
+    let (min, max) = (Pos4D { x: -1.3, y: -1.3, z: -1.3, w: -1.3 }, Pos4D { x: 1.3, y: 1.3, z: 1.3, w: 1.3 });
+
+    let sphere = marching_pentachora(|pos| pos.len() - 1.0, min, max, 6, Color::White);
+    let torus = marching_pentachora(|pos| {
+        let ring = (pos.x * pos.x + pos.y * pos.y).sqrt() - 0.8;
+
+        ring * ring + pos.z * pos.z + pos.w * pos.w - 0.4 * 0.4
+    }, min, max, 8, Color::White);
+
+    for object in [&sphere, &torus] {
+        // Every triangle borders exactly two tetrahedra
+        assert!(!object.cells.is_empty());
+        assert_eq!(object.faces.len() * 2, object.cells.len() * 4);
+        assert_eq!(euler(object), 0);
+        assert!(object.edges.iter().all(|edge| edge.r > 0));
+    }
+
+    assert!(sphere.nodes.iter().all(|node| (node.pos.len() - 1.0).abs() < 0.1));
+
+    // Cells are oriented towards the outside, which for the sphere is the direction of their centers
+    for cell in sphere.cells.iter() {
+        let [a, b, c, d] = [cell.node_a_index, cell.node_b_index, cell.node_c_index, cell.node_d_index].map(|i| sphere.nodes[i].pos);
+        let center = (a + b + c + d) / 4.0;
+
+        assert!(determinant([b - a, c - a, d - a, center]) > 0.0);
+    }
+}
+
+fn determinant(rows: [Pos4D; 4]) -> f32 {
+    let m = rows.map(|row| [row.x, row.y, row.z, row.w]);
+
+    // Sum over all permutations of the columns, with the sign of the permutation
+    let mut total = 0.0;
+    for i in 0..4 {
+        for j in (0..4).filter(|&j| j != i) {
+            for k in (0..4).filter(|&k| k != i && k != j) {
+                let l = 6 - i - j - k;
+                let order = [i, j, k, l];
+                let inversions = (0..4).flat_map(|p| (p + 1..4).map(move |q| (p, q))).filter(|&(p, q)| order[p] > order[q]).count();
+                let sign = if inversions % 2 == 0 { 1.0 } else { -1.0 };
+
+                total += sign * m[0][i] * m[1][j] * m[2][k] * m[3][l];
+            }
+        }
+    }
+
+    total
+}
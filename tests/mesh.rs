@@ -0,0 +1,127 @@
+use n_renderer::{
+    mesh::Diagnostic,
+    object::{Edge, Face, Node, Object},
+    pos::Pos3D,
+    render::Color,
+    shapes::{create_3_cube, create_4_cube},
+};
+
+fn face(a: usize, b: usize, c: usize) -> Face {
+    Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 }
+}
+
+fn edge(a: usize, b: usize) -> Edge {
+    Edge { node_a_index: a, node_b_index: b, r: 1 }
+}
+
+fn node(x: f32, y: f32, z: f32, r: usize) -> Node<Pos3D> {
+    Node { pos: Pos3D { x, y, z }, color: Color::White, r }
+}
+
+#[test]
+fn generated_shapes_are_valid() {
+    assert!(create_3_cube(1.0).validate().is_empty());
+
+    // Every edge of the tesseract is shared by three squares, which can not all agree on a winding
+    let diagnostics = create_4_cube(1.0).validate();
+    let non_manifold = diagnostics.iter().filter(|d| matches!(d, Diagnostic::NonManifoldEdge { faces, .. } if faces.len() == 3)).count();
+
+    assert_eq!(non_manifold, 32);
+    assert!(diagnostics.iter().all(|d| matches!(d, Diagnostic::NonManifoldEdge { .. } | Diagnostic::InconsistentWinding { .. })));
+}
+
+#[test]
+fn broken_faces_are_reported_and_repaired() {
+    let nodes = vec![
+        node(0.0, 0.0, 0.0, 0),
+        node(1.0, 0.0, 0.0, 0),
+        node(0.0, 1.0, 0.0, 0),
+        node(2.0, 0.0, 0.0, 0),
+        node(5.0, 5.0, 5.0, 0),
+        node(5.0, 5.0, 5.0, 1),
+    ];
+    let faces = vec![face(0, 1, 2), face(0, 1, 9), face(0, 1, 3), face(1, 1, 2), face(2, 1, 0), face(1, 2, 0)];
+    let mut object = Object::new(nodes, faces);
+
+    assert_eq!(object.validate(), vec![
+        Diagnostic::IndexOutOfRange { face: 1, index: 9 },
+        Diagnostic::DegenerateFace { face: 2 },
+        Diagnostic::DegenerateFace { face: 3 },
+        Diagnostic::DuplicateFace { face: 4, original: 0 },
+        Diagnostic::DuplicateFace { face: 5, original: 0 },
+        Diagnostic::UnreferencedNode { node: 4 },
+        Diagnostic::UnreferencedNode { node: 5 },
+    ]);
+
+    // Only the node drawn as a point stays besides the nodes of the remaining face
+    let remaining = object.repair();
+    assert_eq!(object.faces, vec![face(0, 1, 2)]);
+    assert_eq!(object.nodes.len(), 4);
+    assert_eq!(remaining, vec![Diagnostic::UnreferencedNode { node: 3 }]);
+}
+
+#[test]
+fn repair_fixes_the_winding_of_flipped_faces() {
+    let mut cube = create_3_cube(1.0);
+    cube.faces[3].flip();
+    cube.faces[7].flip();
+
+    assert!(cube.validate().iter().any(|d| matches!(d, Diagnostic::InconsistentWinding { .. })));
+
+    assert!(cube.repair().is_empty());
+    assert_eq!(cube.faces, create_3_cube(1.0).faces);
+}
+
+#[test]
+fn weld_merges_nodes_across_grid_cells() {
+    // 0.0002 apart but on different sides of a 1/100 boundary
+    let nodes = vec![node(0.0099, 0.0, 0.0, 1), node(1.0, 0.0, 0.0, 1), node(0.0101, 0.0, 0.0, 2), node(0.0, 1.0, 0.0, 1)];
+    let mut object = Object::new(nodes, vec![face(0, 1, 3), face(2, 1, 3), face(0, 2, 1)]);
+    object.edges = vec![edge(0, 1), edge(1, 2), edge(0, 2)];
+
+    object.weld(0.001);
+
+    // The first node of every group stays, the collapsed face and edge are removed and so are the duplicates
+    assert_eq!(object.nodes.iter().map(|n| n.r).collect::<Vec<_>>(), vec![1, 1, 1]);
+    assert_eq!(object.nodes[0].pos.x, 0.0099);
+    assert_eq!(object.faces, vec![face(0, 1, 2)]);
+    assert_eq!(object.edges, vec![edge(0, 1)]);
+}
+
+#[test]
+fn weld_keeps_small_objects_intact() {
+    let mut cube = create_3_cube(0.001);
+    cube.weld(0.0001);
+    assert_eq!(cube.nodes.len(), 8);
+    assert_eq!(cube.faces.len(), 12);
+
+    // The same object with every node duplicated welds back to the original
+    let mut tesseract = create_4_cube(0.001);
+    let original = tesseract.clone();
+    let offset = tesseract.nodes.len();
+
+    tesseract.nodes.extend_from_within(..);
+    tesseract.faces = tesseract.faces.iter().map(|f| face(f.node_a_index + offset, f.node_b_index + offset, f.node_c_index + offset)).collect();
+    tesseract.weld(0.0001);
+
+    assert_eq!(tesseract.nodes.len(), original.nodes.len());
+    assert_eq!(tesseract.faces.iter().map(|f| [f.node_a_index, f.node_b_index, f.node_c_index]).collect::<Vec<_>>(),
+        original.faces.iter().map(|f| [f.node_a_index, f.node_b_index, f.node_c_index]).collect::<Vec<_>>());
+}
+
+#[test]
+fn nearly_collinear_faces_are_degenerate() {
+    // Points on a line away from the origin, which rounding moves slightly off the line
+    let (start, direction) = (Pos3D { x: 1.1, y: 2.2, z: 3.3 }, Pos3D { x: 0.3, y: 0.7, z: -0.2 });
+    let on_line = |t: f32| {
+        let pos = start + direction * t;
+        node(pos.x, pos.y, pos.z, 0)
+    };
+
+    let collinear = Object::new(vec![on_line(0.0), on_line(1.7), on_line(3.9)], vec![face(0, 1, 2)]);
+    assert_eq!(collinear.validate(), vec![Diagnostic::DegenerateFace { face: 0 }]);
+
+    // A thin sliver still has an area
+    let sliver = Object::new(vec![node(0.0, 0.0, 0.0, 0), node(1.0, 0.0, 0.0, 0), node(0.5, 1e-3, 0.0, 0)], vec![face(0, 1, 2)]);
+    assert!(sliver.validate().is_empty());
+}
@@ -0,0 +1,87 @@
+use n_renderer::{
+    object::{Edge, Object},
+    pos::Pos4D,
+    shapes::{create_duoprism, create_polygon},
+};
+
+fn euler<T>(object: &Object<T>) -> i64 {
+    object.nodes.len() as i64 - object.edges.len() as i64 + object.faces.len() as i64 - object.cells.len() as i64
+}
+
+#[test]
+fn product_of_polygons() {
+    let (triangle, square) = (create_polygon(3, 1.0), create_polygon(4, 1.0));
+    let product = Object::product(&triangle, &square);
+
+    // The triangle times the 5 edges of the square split along its diagonal and the 3 edges of the triangle
+    // times the two halves of the square, every prism split into 3 tetrahedra
+    assert_eq!(product.nodes.len(), 12);
+    assert_eq!(product.cells.len(), (5 + 3 * 2) * 3);
+    assert_eq!(product.nodes[4].pos, Pos4D { x: triangle.nodes[1].pos.x, y: triangle.nodes[1].pos.y, z: 1.0, w: 0.0 });
+
+    // Without diagonals inside either polygon the product is only its surface
+    let product = Object::product(&triangle, &triangle);
+    assert_eq!(product.faces.len() * 2, product.cells.len() * 4);
+    assert_eq!(euler(&product), 0);
+}
+
+#[test]
+#[should_panic(expected = "planar")]
+fn product_rejects_objects_outside_the_plane() {
+    let mut square = create_polygon(4, 1.0);
+    square.nodes[0].pos.z = 0.5;
+
+    Object::product(&create_polygon(3, 1.0), &square);
+}
+
+#[test]
+fn duoprism_is_closed() {
+    let duoprism = create_duoprism(5, 4, 1.0);
+
+    // Pentagonal prisms around the square and square prisms around the pentagon
+    assert_eq!(duoprism.nodes.len(), 20);
+    assert_eq!(duoprism.cells.len(), 4 * 3 * 3 + 5 * 2 * 3);
+    assert_eq!(duoprism.faces.len() * 2, duoprism.cells.len() * 4);
+    assert_eq!(euler(&duoprism), 0);
+}
+
+#[test]
+fn prism_and_pyramids_of_a_polygon() {
+    let square = create_polygon(4, 1.0);
+
+    // A cube made of the two halves of the square extruded, the walls, caps and the triangles inside
+    let prism = Object::prism(&square, 1.0);
+    assert_eq!(prism.nodes.len(), 8);
+    assert_eq!(prism.faces.len(), 2 * 2 + 5 * 2 + 2 * 2);
+    assert_eq!(prism.cells.len(), 2 * 3);
+    assert_eq!(prism.nodes.iter().filter(|node| node.pos.w == 0.5).count(), 4);
+
+    let pyramid = Object::pyramid(&square, Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 1.0 });
+    assert_eq!((pyramid.nodes.len(), pyramid.edges.len(), pyramid.faces.len(), pyramid.cells.len()), (5, 9, 7, 2));
+
+    // Two pyramids glued along their base, with the base triangles inside
+    let bipyramid = Object::bipyramid(&square, 1.0);
+    assert_eq!((bipyramid.nodes.len(), bipyramid.cells.len()), (6, 4));
+    assert_eq!(euler(&bipyramid), 1);
+}
+
+#[test]
+fn edges_along_w_are_visible() {
+    let square = create_polygon(4, 1.0);
+    let along_w = |object: &Object<Pos4D>, edge: &Edge| {
+        let (a, b) = (object.nodes[edge.node_a_index].pos, object.nodes[edge.node_b_index].pos);
+        (a.x, a.y, a.z) == (b.x, b.y, b.z)
+    };
+
+    // The 4 edges between the caps of the prism are as thick as the outline of the square
+    let prism = Object::prism(&square, 1.0);
+    let vertical: Vec<&Edge> = prism.edges.iter().filter(|edge| along_w(&prism, edge)).collect();
+    assert_eq!(vertical.len(), 4);
+    assert!(vertical.iter().all(|edge| edge.r == 5));
+
+    // The same goes for the edges to the apexes
+    let bipyramid = Object::bipyramid(&square, 1.0);
+    let apex_edges: Vec<&Edge> = bipyramid.edges.iter().filter(|edge| edge.node_b_index >= 4).collect();
+    assert_eq!(apex_edges.len(), 8);
+    assert!(apex_edges.iter().all(|edge| edge.r == 5));
+}
@@ -0,0 +1,14 @@
+use n_renderer::pos::{Pos3D, Pos4D};
+
+#[test]
+fn subtraction_takes_rhs_from_self() {
+    let a = Pos4D { x: 5.0, y: 4.0, z: 3.0, w: 2.0 };
+    let b = Pos4D { x: 1.0, y: 1.0, z: 1.0, w: 1.0 };
+
+    assert_eq!(a - b, Pos4D { x: 4.0, y: 3.0, z: 2.0, w: 1.0 });
+    assert_eq!(b + (a - b), a);
+
+    // The same order as in 3D
+    let (a3, b3) = (Pos3D { x: 5.0, y: 4.0, z: 3.0 }, Pos3D { x: 1.0, y: 1.0, z: 1.0 });
+    assert_eq!(a3 - b3, Pos3D { x: 4.0, y: 3.0, z: 2.0 });
+}
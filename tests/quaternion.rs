@@ -0,0 +1,92 @@
+use std::f32::consts::PI;
+
+use n_renderer::{
+    matrix::{Matrix3x3, Matrix4x4},
+    pos::{Len, Pos3D, Pos4D, RotationPlane},
+    quaternion::{DoubleQuaternion, Quaternion},
+};
+
+fn close(a: Quaternion, b: Quaternion) -> bool {
+    (a + b * -1.0).len() < 1e-5
+}
+
+/// Both quaternions negated describe the same rotation
+fn same_rotation(a: DoubleQuaternion, b: DoubleQuaternion) -> bool {
+    (close(a.left, b.left) && close(a.right, b.right)) || (close(a.left, b.left * -1.0) && close(a.right, b.right * -1.0))
+}
+
+fn points() -> [Pos4D; 3] {
+    [
+        Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 },
+        Pos4D { x: 0.3, y: -1.2, z: 0.7, w: 2.0 },
+        Pos4D { x: -0.5, y: 0.25, z: -2.0, w: 0.1 },
+    ]
+}
+
+#[test]
+fn quaternion_matrix_round_trip() {
+    let rotations = [
+        Quaternion::identity(),
+        Quaternion::from_axis_angle(Pos3D { x: 0.0, y: 0.0, z: 1.0 }, PI / 2.0),
+        Quaternion::from_axis_angle(Pos3D { x: 1.0, y: 2.0, z: -0.5 }, 2.5),
+        // Close to half a turn, where the trace is negative
+        Quaternion::from_axis_angle(Pos3D { x: -0.2, y: 0.1, z: 1.0 }, 3.1),
+    ];
+
+    for q in rotations {
+        let back = Quaternion::from(Matrix3x3::from(q));
+
+        assert!(close(back, q) || close(back, q * -1.0), "{:?} came back as {:?}", q, back);
+    }
+}
+
+#[test]
+fn quaternion_rotates_counterclockwise() {
+    // A quarter turn around z takes x to y, the same as the matrix
+    let q = Quaternion::from_axis_angle(Pos3D { x: 0.0, y: 0.0, z: 1.0 }, PI / 2.0);
+    let x = Pos3D { x: 1.0, y: 0.0, z: 0.0 };
+
+    assert!((q.rotate(x) - Pos3D { x: 0.0, y: 1.0, z: 0.0 }).len() < 1e-6);
+    assert!((Matrix3x3::from(q) * x - q.rotate(x)).len() < 1e-6);
+}
+
+#[test]
+fn double_quaternion_from_plane_rotations() {
+    let angle: f32 = 0.8;
+    let half = Quaternion::new((angle / 2.0).cos(), 0.0, 0.0, (angle / 2.0).sin());
+
+    // In the xy plane, taking x towards y, is the 3D rotation around z
+    let xy = DoubleQuaternion::from(RotationPlane::get_rot_mat_4d(RotationPlane::YX, angle));
+    assert!(same_rotation(xy, DoubleQuaternion::new(half, half.conjugate())), "{:?}", xy);
+
+    // In the zw plane, taking w towards z, multiplies by the same quaternion on both sides
+    let zw = DoubleQuaternion::from(RotationPlane::get_rot_mat_4d(RotationPlane::ZW, angle));
+    assert!(same_rotation(zw, DoubleQuaternion::new(half, half)), "{:?}", zw);
+}
+
+#[test]
+fn double_quaternion_agrees_with_the_matrix() {
+    let planes = [RotationPlane::YX, RotationPlane::XZ, RotationPlane::XW, RotationPlane::YZ, RotationPlane::ZW, RotationPlane::WY];
+
+    for plane in planes {
+        let matrix = RotationPlane::get_rot_mat_4d(plane, 1.1);
+        let rotation = DoubleQuaternion::from(matrix);
+
+        for pos in points() {
+            assert!((rotation.rotate(pos) - matrix * pos).len() < 1e-5);
+        }
+
+        // And back to the same matrix
+        for pos in points() {
+            assert!((Matrix4x4::from(rotation) * pos - matrix * pos).len() < 1e-5);
+        }
+    }
+
+    // Composition applies the right hand side first, like matrices
+    let (a, b) = (RotationPlane::get_rot_mat_4d(RotationPlane::XW, 0.4), RotationPlane::get_rot_mat_4d(RotationPlane::YZ, 1.3));
+    let composed = DoubleQuaternion::from(a) * DoubleQuaternion::from(b);
+
+    for pos in points() {
+        assert!((composed.rotate(pos) - a * (b * pos)).len() < 1e-5);
+    }
+}
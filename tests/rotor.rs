@@ -0,0 +1,99 @@
+use std::f32::consts::PI;
+
+use n_renderer::{
+    matrix::Matrix4x4,
+    pos::{Len, Pos4D, RotationPlane},
+    rotor::{Bivector, Rotor},
+};
+
+/// Rotors are equal up to sign, R and -R describe the same rotation
+fn same_rotation(a: Rotor, b: Rotor) -> bool {
+    let difference = |sign: f32| (a.s - b.s * sign).abs() + (a.b + b.b * -sign).len() + (a.xyzw - b.xyzw * sign).abs();
+
+    difference(1.0) < 1e-5 || difference(-1.0) < 1e-5
+}
+
+fn close(a: Pos4D, b: Pos4D) -> bool {
+    (a - b).len() < 1e-5
+}
+
+fn points() -> [Pos4D; 3] {
+    [
+        Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 },
+        Pos4D { x: 0.3, y: -1.2, z: 0.7, w: 2.0 },
+        Pos4D { x: -0.5, y: 0.25, z: -2.0, w: 0.1 },
+    ]
+}
+
+#[test]
+fn rotor_from_plane() {
+    let x = Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 };
+    let quarter = Rotor::from_plane(RotationPlane::XY.into(), PI / 2.0);
+
+    // Turning x towards y, and the magnitude of the plane does not matter
+    assert!(close(quarter * x, Pos4D { x: 0.0, y: 1.0, z: 0.0, w: 0.0 }));
+    assert!(same_rotation(quarter, Rotor::from_plane(Bivector::from(RotationPlane::XY) * 3.0, PI / 2.0)));
+
+    // The reversed plane turns the other way
+    let back = Rotor::from_plane(RotationPlane::YX.into(), PI / 2.0);
+    assert!(close(back * x, Pos4D { x: 0.0, y: -1.0, z: 0.0, w: 0.0 }));
+    assert!(same_rotation(back, quarter.reverse()));
+
+    // A plane in general position spanned by two vectors turns the first into the second
+    let (a, b) = (Pos4D { x: 1.0, y: 0.0, z: 0.0, w: 0.0 }, Pos4D { x: 0.0, y: 0.6, z: 0.0, w: 0.8 });
+    assert!(close(Rotor::from_plane(Bivector::wedge(a, b), PI / 2.0) * a, b));
+}
+
+#[test]
+fn rotor_exp_and_log() {
+    let bivectors = [
+        Bivector::new(0.5, 0.0, 0.0, 0.0, 0.0, 0.0),
+        Bivector::new(0.3, -0.2, 0.4, 0.1, 0.0, 0.6),
+        // Two rotations in orthogonal planes
+        Bivector::new(1.2, 0.0, 0.0, 0.0, 0.0, 0.7),
+    ];
+
+    for bivector in bivectors {
+        let log = Rotor::from_bivector(bivector).log();
+
+        assert!((log + bivector * -1.0).len() < 1e-5, "{:?} came back as {:?}", bivector, log);
+    }
+
+    assert!(Rotor::identity().log().len() < 1e-6);
+}
+
+#[test]
+fn rotor_slerp() {
+    let a = Rotor::from_plane(RotationPlane::XZ.into(), 0.3);
+    let b = Rotor::from_bivector(Bivector::new(0.2, 0.0, 0.9, -0.4, 0.0, 0.5));
+
+    assert!(same_rotation(a.slerp(b, 0.0), a));
+    assert!(same_rotation(a.slerp(b, 1.0), b));
+
+    // Halfway along a single plane is half the angle
+    let (start, end) = (Rotor::identity(), Rotor::from_plane(RotationPlane::ZW.into(), 2.0));
+    assert!(same_rotation(start.slerp(end, 0.5), Rotor::from_plane(RotationPlane::ZW.into(), 1.0)));
+}
+
+#[test]
+fn rotor_agrees_with_the_matrix() {
+    // The bivector turns x towards y, the matrix is named after the opposite plane
+    let pairs = [(RotationPlane::XY, RotationPlane::YX), (RotationPlane::XW, RotationPlane::WX), (RotationPlane::ZW, RotationPlane::WZ)];
+
+    for (bivector, matrix) in pairs {
+        let rotor = Rotor::from_plane(bivector.into(), 0.7);
+        let matrix = RotationPlane::get_rot_mat_4d(matrix, 0.7);
+
+        for pos in points() {
+            assert!(close(rotor * pos, matrix * pos));
+        }
+    }
+
+    let rotor = Rotor::from_bivector(Bivector::new(0.3, -0.2, 0.4, 0.1, 0.0, 0.6));
+    for pos in points() {
+        assert!(close(Matrix4x4::from(rotor) * pos, rotor * pos));
+
+        // Rotations keep lengths
+        assert!(((rotor * pos).len() - pos.len()).abs() < 1e-5);
+    }
+}
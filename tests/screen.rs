@@ -0,0 +1,39 @@
+use n_renderer::render::{Color, Filter, Screen};
+
+/// Single pixel of 2 x 2 samples, with the top row written in `color`
+fn half_covered(color: Color, rest: Option<Color>) -> [u8; 4] {
+    let mut screen = Screen::with_supersampling(1, 1, 2, Filter::Box);
+
+    for x in 0..2 {
+        screen.write(x, 0, color, 0.5).unwrap();
+
+        if let Some(rest) = rest {
+            screen.write(x, 1, rest, 0.5).unwrap();
+        }
+    }
+
+    // Nothing shows up until the screen is resolved
+    assert_eq!(screen.get_slice(), [0, 0, 0, 0]);
+    screen.resolve();
+
+    screen.get_slice().try_into().unwrap()
+}
+
+#[test]
+fn resolve_averages_in_linear_light() {
+    // Half of full brightness in linear light is 188 in srgb, not 128
+    assert_eq!(half_covered(Color::White, Some(Color::Black)), [188, 188, 188, 255]);
+
+    // Empty samples only lower the coverage, not the brightness
+    assert_eq!(half_covered(Color::White, None), [255, 255, 255, 128]);
+    assert_eq!(half_covered(Color::Red, None), [255, 0, 0, 128]);
+}
+
+#[test]
+fn resolve_without_supersampling_keeps_the_pixels() {
+    let mut screen = Screen::new(2, 1);
+    screen.write(1, 0, Color::Green, 0.5).unwrap();
+    screen.resolve();
+
+    assert_eq!(screen.get_slice(), [0, 0, 0, 0, 0, 255, 0, 255]);
+}
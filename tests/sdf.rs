@@ -0,0 +1,83 @@
+use n_renderer::{
+    pos::{Pos4D, RotationPlane},
+    render::{Filter, Screen},
+    rotor::Rotor,
+    sdf::{MarchMode, RayMarcher, Sdf},
+};
+
+const ORIGIN: Pos4D = Pos4D { x: 0.0, y: 0.0, z: 0.0, w: 0.0 };
+
+fn pos(x: f32, y: f32, z: f32, w: f32) -> Pos4D {
+    Pos4D { x, y, z, w }
+}
+
+#[test]
+fn primitive_distances() {
+    let sphere = Sdf::Hypersphere { center: pos(0.0, 0.0, 0.0, 1.0), r: 0.5 };
+    assert!((sphere.distance(pos(0.0, 0.0, 0.0, 3.0)) - 1.5).abs() < 1e-6);
+
+    let tesseract = Sdf::Tesseract { center: ORIGIN, r: 1.0 };
+    assert!((tesseract.distance(pos(0.5, 0.0, 0.0, 0.0)) + 0.5).abs() < 1e-6);
+    assert!((tesseract.distance(pos(2.0, 2.0, 0.0, 0.0)) - 2.0_f32.sqrt()).abs() < 1e-6);
+
+    // Every point of the clifford torus is on both circles at once
+    let r = 2.0_f32.sqrt();
+    let torus = Sdf::CliffordTorus { r: 2.0, thickness: 0.1 };
+    assert!((torus.distance(pos(r, 0.0, 0.0, r)) + 0.1).abs() < 1e-5);
+
+    // Rotating the tesseract by 45 degrees in xw moves its corners onto the x and w axes
+    assert!(tesseract.distance(pos(1.4, 0.0, 0.0, 0.0)) > 0.0);
+    let rotated = Sdf::Tesseract { center: ORIGIN, r: 1.0 }.rotated(Rotor::from_plane(RotationPlane::XW.into(), std::f32::consts::FRAC_PI_4));
+    assert!(rotated.distance(pos(1.4, 0.0, 0.0, 0.0)) < 0.0);
+    assert!(rotated.distance(pos(0.0, 0.0, 0.0, 1.4)) < 0.0);
+}
+
+#[test]
+fn smooth_union_blends_below_the_union() {
+    let a = Sdf::Hypersphere { center: pos(-0.6, 0.0, 0.0, 0.0), r: 0.5 };
+    let b = Sdf::Hypersphere { center: pos(0.6, 0.0, 0.0, 0.0), r: 0.5 };
+    let union = Sdf::Union(Box::new(a.clone()), Box::new(b.clone()));
+    let smooth = Sdf::SmoothUnion(Box::new(a), Box::new(b), 0.5);
+
+    // The gap between the spheres is filled in, far away both agree
+    assert!(union.distance(ORIGIN) > 0.0);
+    assert!(smooth.distance(ORIGIN) < 0.0);
+    assert_eq!(union.distance(pos(5.0, 0.0, 0.0, 0.0)), smooth.distance(pos(5.0, 0.0, 0.0, 0.0)));
+
+    let normal = union.normal(pos(1.1, 0.0, 0.0, 0.0));
+    assert!((normal.x - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn ray_marcher_renders_slices_and_projections() {
+    let sphere = Sdf::Hypersphere { center: ORIGIN, r: 0.5 };
+
+    for (mode, hit) in [(MarchMode::Slice(0.0), true), (MarchMode::Slice(0.6), false), (MarchMode::Projection, true)] {
+        let mut screen = Screen::new(16, 16);
+        RayMarcher::new(mode, 1.0).render(&sphere, &mut screen);
+
+        assert_eq!(screen.pixel_depth(8, 8).is_some(), hit);
+        assert_eq!(screen.pixel_depth(0, 0), None);
+    }
+
+    // The front of the slice is lit from the eye and nearer than the far plane
+    let mut screen = Screen::new(16, 16);
+    RayMarcher::new(MarchMode::Slice(0.0), 1.0).render(&sphere, &mut screen);
+    screen.resolve();
+
+    let depth = screen.pixel_depth(8, 8).unwrap();
+    assert!(depth > 0.0 && depth < 0.6);
+    assert!(screen.get_slice()[(8 * 16 + 8) * 4..][..3].iter().any(|&c| c > 0));
+}
+
+#[test]
+fn ray_marcher_leaves_resolving_to_the_caller() {
+    let sphere = Sdf::Hypersphere { center: ORIGIN, r: 0.5 };
+    let mut screen = Screen::with_supersampling(16, 16, 2, Filter::Box);
+
+    RayMarcher::new(MarchMode::Slice(0.0), 1.0).render(&sphere, &mut screen);
+    assert!(screen.get_slice().iter().all(|&c| c == 0));
+
+    screen.resolve();
+    assert!(screen.get_slice()[(8 * 16 + 8) * 4..][..4].iter().any(|&c| c > 0));
+}
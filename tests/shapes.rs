@@ -0,0 +1,126 @@
+use std::{collections::HashSet, sync::{Arc, Mutex}};
+
+use n_renderer::{
+    complex::Complex,
+    object::Object,
+    pos::{Len, Pos4D},
+    projection::{Projection, ProjectionType},
+    render::{Color, Screen},
+    shapes::*,
+};
+
+#[test]
+fn hypersphere_is_a_closed_3_manifold() {
+    for subdivisions in 0..2 {
+        let sphere = create_hypersphere(subdivisions, 2.0);
+
+        // The Euler characteristic of the 3-sphere is 0
+        let euler = sphere.nodes.len() as i64 - sphere.edges.len() as i64 + sphere.faces.len() as i64 - sphere.cells.len() as i64;
+        assert_eq!(euler, 0);
+
+        // Every triangle is shared by exactly two tetrahedra
+        assert_eq!(sphere.faces.len() * 2, sphere.cells.len() * 4);
+        assert!(sphere.nodes.iter().all(|node| (node.pos.len() - 2.0).abs() < 1e-4));
+
+        // No duplicate nodes to weld
+        let mut welded = sphere.clone();
+        welded.weld(1e-3);
+        assert_eq!(welded.nodes.len(), sphere.nodes.len());
+    }
+
+    assert_eq!(create_hypersphere(0, 1.0).cells.len(), 600);
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_4_sphere_is_a_hypersphere() {
+    // 500 nodes need one subdivision of the 600-cell
+    let sphere = create_4_sphere(500, 1.5);
+
+    assert_eq!(sphere.nodes.len(), create_hypersphere(1, 1.5).nodes.len());
+    assert!(sphere.nodes.iter().all(|node| (node.pos.len() - 1.5).abs() < 1e-4));
+    assert_eq!(create_4_sphere(100, 1.5).cells.len(), 600);
+}
+
+#[test]
+fn hypersphere_edges_are_drawn() {
+    let sphere = create_hypersphere(0, 1.0);
+    assert!(sphere.edges.iter().all(|edge| edge.r > 0));
+
+    // Without faces, and with nodes of radius 0, anything on the screen comes from the edges
+    let wireframe = Object { faces: Vec::new(), ..sphere };
+    let screen = Arc::new(Mutex::new(Screen::new(64, 64)));
+    wireframe.draw(Arc::clone(&screen), Projection::new(ProjectionType::Stereographic, 0.3));
+
+    let screen = screen.lock().unwrap();
+    assert!(screen.get_slice().chunks(4).any(|pixel| pixel[3] > 0));
+}
+
+#[test]
+fn parametric_surfaces_are_watertight() {
+    let sphere = create_3_sphere(500, 2.0);
+    let torus = create_torus(24, 1.0);
+
+    for (object, euler) in [(&sphere, 2), (&torus, 0)] {
+        assert!(object.validate().is_empty(), "{:?}", object.validate());
+        assert_eq!(object.nodes.len() as i64 - (object.faces.len() * 3 / 2) as i64 + object.faces.len() as i64, euler);
+        assert_eq!(object.normals.len(), object.nodes.len());
+    }
+
+    // Normals point out of the sphere, in the direction the faces are wound
+    assert!(sphere.nodes.iter().zip(sphere.normals.iter()).all(|(node, &normal)| ((node.pos / 2.0) >> normal) > 0.99));
+}
+
+#[test]
+fn four_dimensional_shapes_are_closed() {
+    let hypersurfaces = [
+        create_duocylinder(12, 1.0),
+        create_spherinder(12, 1.0, 0.5),
+        create_cubinder(12, 1.0),
+        create_tiger(12, 1.0, 1.0, 0.3),
+        create_ditorus(12, 1.0, 0.5, 0.2),
+    ];
+
+    for object in hypersurfaces.iter() {
+        // Every triangle of a closed hypersurface borders exactly two tetrahedra
+        assert_eq!(object.faces.len() * 2, object.cells.len() * 4);
+
+        let euler = object.nodes.len() as i64 - object.edges.len() as i64 + object.faces.len() as i64 - object.cells.len() as i64;
+        assert_eq!(euler, 0);
+    }
+
+    assert!(create_clifford_torus(12, 1.0).validate().is_empty());
+}
+
+#[test]
+fn complex_graphs() {
+    let euler = |object: &Object<Pos4D>| {
+        let edges: HashSet<(usize, usize)> = object.faces.iter().flat_map(|face| {
+            let [a, b, c] = [face.node_a_index, face.node_b_index, face.node_c_index];
+
+            [(a, b), (b, c), (c, a)].map(|(p, q)| (p.min(q), p.max(q)))
+        }).collect();
+
+        object.nodes.len() as i64 - edges.len() as i64 + object.faces.len() as i64
+    };
+
+    // Both branches of the square root make up a single disk, with no seam along the negative real axis
+    let sqrt = create_complex_sqrt(8, 1.0);
+    assert!(sqrt.validate().is_empty(), "{:?}", sqrt.validate());
+    assert_eq!(euler(&sqrt), 1);
+
+    for node in sqrt.nodes.iter() {
+        let (z, f) = (Complex::new(node.pos.x, node.pos.y), Complex::new(node.pos.z, node.pos.w));
+        assert!((f * f - z).abs() < 1e-4);
+    }
+
+    let square = create_complex_square(8, 1.0);
+    assert_eq!(euler(&square), 1);
+    assert!(square.nodes.iter().all(|node| matches!(node.color, Color::HSV(..))));
+
+    // Along the real axis exp is real and positive, colored red
+    let exp = create_complex_exp(8, 1.0);
+    let real = exp.nodes.iter().find(|node| node.pos.y.abs() < 1e-6 && node.pos.x.abs() < 1e-6).unwrap();
+    assert!((real.pos.z - 1.0).abs() < 1e-6 && real.pos.w.abs() < 1e-6);
+    assert_eq!(real.color, Color::HSV(0, 255, 255));
+}
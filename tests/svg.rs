@@ -0,0 +1,38 @@
+use n_renderer::{
+    object::{CullMode, Face, Node, Object},
+    pos::Pos3D,
+    projection::{Projection, ProjectionType},
+    render::Color,
+    svg::to_svg,
+};
+
+/// Two triangles on top of each other facing the camera, a red one wound towards it and a blue one wound away
+fn triangles() -> Object<Pos3D> {
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (0.0, 1.0)];
+    let node = |(x, y): (f32, f32), color| Node { pos: Pos3D { x, y, z: 0.0 }, color, r: 0 };
+
+    let nodes = corners.map(|corner| node(corner, Color::Red)).into_iter().chain(corners.map(|corner| node(corner, Color::Blue))).collect();
+    let face = |a, b, c| Face { node_a_index: a, node_b_index: b, node_c_index: c, r: 1 };
+
+    Object::new(nodes, vec![face(0, 2, 1), face(3, 4, 5)])
+}
+
+fn svg(object: &Object<Pos3D>) -> String {
+    to_svg(object, (64, 64), Projection::new(ProjectionType::Collapse, 20.0))
+}
+
+#[test]
+fn culled_triangles_are_left_out() {
+    let (red, blue) = ("rgb(255,0,0)", "rgb(0,0,255)");
+
+    let back = svg(&triangles());
+    assert_eq!(back.matches("<polygon").count(), 1);
+    assert!(back.contains(red) && !back.contains(blue), "{}", back);
+
+    let front = svg(&Object { cull: CullMode::Front, ..triangles() });
+    assert_eq!(front.matches("<polygon").count(), 1);
+    assert!(front.contains(blue) && !front.contains(red), "{}", front);
+
+    let none = svg(&Object { cull: CullMode::None, ..triangles() });
+    assert_eq!(none.matches("<polygon").count(), 2);
+}
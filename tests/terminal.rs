@@ -0,0 +1,63 @@
+use n_renderer::{
+    render::{Color, Screen},
+    terminal::{to_ansi, TerminalMode},
+};
+
+/// Screen with the given pixels set to `color`
+fn screen(width: usize, height: usize, pixels: &[(usize, usize)], color: Color) -> Screen {
+    let mut screen = Screen::new(width, height);
+    for &(x, y) in pixels {
+        screen.write(x, y, color, 0.5).unwrap();
+    }
+
+    screen
+}
+
+fn all_pixels(width: usize, height: usize) -> Vec<(usize, usize)> {
+    (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).collect()
+}
+
+#[test]
+fn half_blocks() {
+    let red = screen(4, 4, &all_pixels(4, 4), Color::Red);
+    let text = to_ansi(&red, 4, 2, TerminalMode::HalfBlock);
+
+    // Two rows of four characters, with a line break between the rows but not after the last one
+    assert_eq!(text.matches('▀').count(), 8);
+    assert_eq!(text.matches("\r\n").count(), 1);
+    assert!(text.ends_with("\x1b[0m"));
+    assert!(text.contains("\x1b[38;2;255;0;0m\x1b[48;2;255;0;0m▀"));
+
+    // Only the top half of the character is lit
+    let top = screen(1, 2, &[(0, 0)], Color::Green);
+    assert_eq!(to_ansi(&top, 1, 1, TerminalMode::HalfBlock), "\x1b[0m\x1b[38;2;0;255;0m▀\x1b[0m");
+}
+
+#[test]
+fn empty_pixels_stay_empty() {
+    let text = to_ansi(&Screen::new(4, 4), 4, 2, TerminalMode::HalfBlock);
+
+    assert!(!text.contains("\x1b[38"));
+    assert_eq!(text.matches(' ').count(), 8);
+}
+
+#[test]
+fn braille_dots() {
+    // Every pixel of a 2 x 4 cell lit, and only the top left one
+    let full = screen(2, 4, &all_pixels(2, 4), Color::White);
+    assert!(to_ansi(&full, 1, 1, TerminalMode::Braille).contains('\u{28ff}'));
+
+    let corner = screen(2, 4, &[(0, 0)], Color::White);
+    assert!(to_ansi(&corner, 1, 1, TerminalMode::Braille).contains('\u{2801}'));
+}
+
+#[test]
+fn large_screens_are_scaled_down() {
+    let red = screen(40, 40, &all_pixels(40, 40), Color::Red);
+    let text = to_ansi(&red, 10, 5, TerminalMode::HalfBlock);
+
+    // Square pixels, two per character row, fit in the height
+    let rows: Vec<&str> = text.split("\r\n").collect();
+    assert_eq!(rows.len(), 5);
+    assert!(rows.iter().all(|row| row.matches('▀').count() == 10));
+}